@@ -4,20 +4,20 @@ use shapemaker::*;
 
 fn artwork() -> Canvas {
     let mut rng = rand::rngs::StdRng::seed_from_u64(42);
-    let mut canvas = Canvas::with_colors(ColorMapping {
-        black: "#000000".into(),
-        white: "#ffffff".into(),
-        red: "#cf0a2b".into(),
-        green: "#22e753".into(),
-        blue: "#2734e6".into(),
-        yellow: "#f8e21e".into(),
-        orange: "#f05811".into(),
-        purple: "#6a24ec".into(),
-        brown: "#a05634".into(),
-        pink: "#e92e76".into(),
-        gray: "#81a0a8".into(),
-        cyan: "#4fecec".into(),
-    });
+    let mut canvas = Canvas::with_colors(ColorMapping::from([
+        ("black", "#000000"),
+        ("white", "#ffffff"),
+        ("red", "#cf0a2b"),
+        ("green", "#22e753"),
+        ("blue", "#2734e6"),
+        ("yellow", "#f8e21e"),
+        ("orange", "#f05811"),
+        ("purple", "#6a24ec"),
+        ("brown", "#a05634"),
+        ("pink", "#e92e76"),
+        ("gray", "#81a0a8"),
+        ("cyan", "#4fecec"),
+    ]));
     canvas.set_grid_size(16, 9);
     canvas.set_background(Black);
 
@@ -36,7 +36,7 @@ fn artwork() -> Canvas {
 
     for (i, (_key, obj)) in canvas
         .layer_unchecked("strands")
-        .objects
+        .objects_mut()
         .iter_mut()
         .sorted_by_key(|(k, _)| *k)
         .enumerate()
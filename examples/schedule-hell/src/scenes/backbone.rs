@@ -10,12 +10,11 @@ pub fn backbone() -> Scene<State> {
         .init(&|canvas, ctx| {
             canvas.clear();
 
-            canvas.colormap = ColorMapping {
-                black: "#000000".to_string(),
-                white: "#FFFFFF".to_string(),
-                purple: "#da40f5".to_string(),
-                ..Default::default()
-            };
+            canvas.colormap = ColorMapping::from([
+                ("black", "#000000"),
+                ("white", "#FFFFFF"),
+                ("purple", "#da40f5"),
+            ]);
 
             canvas.set_grid_size(16, 10);
             canvas.set_background(Black);
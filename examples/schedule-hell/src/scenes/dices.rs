@@ -49,20 +49,20 @@ pub fn setup(canvas: &mut Canvas) {
     //     canvas.grid_size.1 * DICES_GRID.1,
     // );
     canvas.object_sizes.small_circle_radius = 7.0;
-    canvas.colormap = ColorMapping {
-        black: "#000000".into(),
-        white: "#ffffff".into(),
-        red: "#cf0a2b".into(),
-        green: "#22e753".into(),
-        blue: "#2734e6".into(),
-        yellow: "#f8e21e".into(),
-        orange: "#f05811".into(),
-        purple: "#6a24ec".into(),
-        brown: "#a05634".into(),
-        pink: "#e92e76".into(),
-        gray: "#81a0a8".into(),
-        cyan: "#4fecec".into(),
-    };
+    canvas.colormap = ColorMapping::from([
+        ("black", "#000000"),
+        ("white", "#ffffff"),
+        ("red", "#cf0a2b"),
+        ("green", "#22e753"),
+        ("blue", "#2734e6"),
+        ("yellow", "#f8e21e"),
+        ("orange", "#f05811"),
+        ("purple", "#6a24ec"),
+        ("brown", "#a05634"),
+        ("pink", "#e92e76"),
+        ("gray", "#81a0a8"),
+        ("cyan", "#4fecec"),
+    ]);
 }
 
 pub fn place_dice(canvas: &mut Canvas, at: (usize, usize), value: usize) {
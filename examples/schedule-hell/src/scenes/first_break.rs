@@ -17,20 +17,20 @@ fn random_shape(at: Point) -> Object {
 pub fn first_break() -> Scene<State> {
     Scene::<State>::new("first break")
         .init(&|canvas, _| {
-            canvas.colormap = ColorMapping {
-                black: "#676E95".to_string(),
-                white: "#ffffff".to_string(),
-                red: "#ff5572".to_string(),
-                green: "#a9c77d".to_string(),
-                blue: "#82AAFF".to_string(),
-                yellow: "#FFCB6B".to_string(),
-                orange: "#FFCB6B".to_string(),
-                purple: "#C792EA".to_string(),
-                brown: "#ff5572".to_string(),
-                pink: "#C792EA".to_string(),
-                gray: "#ffffff".to_string(),
-                cyan: "#89DDFF".to_string(),
-            };
+            canvas.colormap = ColorMapping::from([
+                ("black", "#676E95"),
+                ("white", "#ffffff"),
+                ("red", "#ff5572"),
+                ("green", "#a9c77d"),
+                ("blue", "#82AAFF"),
+                ("yellow", "#FFCB6B"),
+                ("orange", "#FFCB6B"),
+                ("purple", "#C792EA"),
+                ("brown", "#ff5572"),
+                ("pink", "#C792EA"),
+                ("gray", "#ffffff"),
+                ("cyan", "#89DDFF"),
+            ]);
 
             canvas.clear();
 
@@ -61,7 +61,7 @@ pub fn first_break() -> Scene<State> {
         })
         .on_note("goup", &|canvas, _| {
             let world = canvas.world_region.clone();
-            let shapes = &mut canvas.layer("shapes")?.objects;
+            let shapes = canvas.layer("shapes")?.objects_mut();
 
             for (i, point) in world.iter().enumerate() {
                 let shape = shapes.get_mut(&format!("shape{i}"));
@@ -79,7 +79,7 @@ pub fn first_break() -> Scene<State> {
             for (i, _) in world.iter().enumerate() {
                 canvas
                     .layer("tiling")?
-                    .objects
+                    .objects_mut()
                     .get_mut(&format!("tile{i}"))
                     .unwrap()
                     .recolor(Color::random_except(
@@ -7,20 +7,20 @@ pub fn intro() -> Scene<State> {
             canvas.clear();
             canvas.set_grid_size(16, 9);
             canvas.set_background(Black);
-            canvas.colormap = ColorMapping {
-                black: "#000000".into(),
-                white: "#ffffff".into(),
-                red: "#cf0a2b".into(),
-                green: "#22e753".into(),
-                blue: "#2734e6".into(),
-                yellow: "#f8e21e".into(),
-                orange: "#f05811".into(),
-                purple: "#6a24ec".into(),
-                brown: "#a05634".into(),
-                pink: "#e92e76".into(),
-                gray: "#81a0a8".into(),
-                cyan: "#4fecec".into(),
-            };
+            canvas.colormap = ColorMapping::from([
+                ("black", "#000000"),
+                ("white", "#ffffff"),
+                ("red", "#cf0a2b"),
+                ("green", "#22e753"),
+                ("blue", "#2734e6"),
+                ("yellow", "#f8e21e"),
+                ("orange", "#f05811"),
+                ("purple", "#6a24ec"),
+                ("brown", "#a05634"),
+                ("pink", "#e92e76"),
+                ("gray", "#81a0a8"),
+                ("cyan", "#4fecec"),
+            ]);
 
             let mut kicks = Layer::new("anchor kick");
 
@@ -37,14 +37,19 @@ pub fn intro() -> Scene<State> {
         .on_note("anchor kick", &|canvas, ctx| {
             canvas
                 .layer("anchor kick")?
-                .paint_all_objects(Fill::Translucent(ctx.extra.kick_color, 1.0));
+                .paint_all_objects(Fill::Translucent(
+                    ctx.extra.kick_color.clone(),
+                    1.0,
+                ));
 
             ctx.animate(200, &|t, canvas, _| {
-                canvas.layer("anchor kick")?.objects.values_mut().for_each(
-                    |Object { fill, .. }| {
+                canvas
+                    .layer("anchor kick")?
+                    .objects_mut()
+                    .values_mut()
+                    .for_each(|Object { fill, .. }| {
                         *fill = fill.opacify(1.0 - t);
-                    },
-                );
+                    });
                 Ok(())
             });
 
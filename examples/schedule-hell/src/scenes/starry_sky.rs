@@ -24,11 +24,8 @@ fn sky(cranks: u32, canvas: &mut Canvas) -> Result<()> {
     let theta = Angle::from_ratio(cranks as f32, 72.0);
 
     canvas.clear();
-    canvas.colormap = ColorMapping {
-        black: "#000000".to_string(),
-        white: "#FFFFFF".to_string(),
-        ..Default::default()
-    };
+    canvas.colormap =
+        ColorMapping::from([("black", "#000000"), ("white", "#FFFFFF")]);
 
     canvas.set_background(Color::Black);
     canvas.set_grid_size(16 * 4, 9 * 4);
@@ -19,6 +19,7 @@ impl Angle {
     ///
     /// assert_eq!(Angle::from_ratio(0.5, 1.0).degrees() as usize, 180);
     /// assert_eq!(Angle::from_radians(std::f32::consts::TAU).degrees() as usize, 360);
+    /// assert_eq!(Angle::from_degrees(180.0).radians(), std::f32::consts::PI);
     /// ```
     pub fn from_ratio(amount: f32, of: f32) -> Self {
         Self(amount * Self::TURN.0 / of)
@@ -30,7 +31,7 @@ impl Angle {
 
     pub fn radians(&self) -> f32 {
         // tau better than pi, haters gonna hate <3
-        self.0 * std::f32::consts::TAU / (Self::TURN.0 / 4.0)
+        self.0 * std::f32::consts::TAU / Self::TURN.0
     }
 
     pub fn turns(&self) -> f32 {
@@ -47,6 +48,12 @@ impl Angle {
     }
 }
 
+impl std::hash::Hash for Angle {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        super::hash_floats(&[self.0], state);
+    }
+}
+
 impl std::ops::Sub for Angle {
     type Output = Angle;
 
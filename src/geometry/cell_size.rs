@@ -0,0 +1,269 @@
+use crate::{Region, Topology};
+
+/// Size of the grid's cells, in pixels, and how they are laid out.
+/// Cells can be wider than they are tall, and columns and rows can have their own sizes, to make a typographic grid.
+///
+/// ```
+/// use shapemaker::CellSize;
+///
+/// let cell_size = CellSize::new(20, 10).with_columns([40, 30]);
+/// assert_eq!(cell_size.coords((1.0, 1.0)), (40.0, 10.0));
+/// assert_eq!(cell_size.coords((2.5, 0.5)), (80.0, 5.0));
+/// assert_eq!(cell_size.coords((-1.0, 0.0)), (-20.0, 0.0));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct CellSize {
+    /// Width of the columns that are not in `columns`
+    pub width: usize,
+    /// Height of the rows that are not in `rows`
+    pub height: usize,
+    /// Widths of the first columns, from left to right
+    pub columns: Vec<usize>,
+    /// Heights of the first rows, from top to bottom
+    pub rows: Vec<usize>,
+    /// Only square grids use `columns` and `rows`: cells of other topologies all have the same size.
+    pub topology: Topology,
+}
+
+impl CellSize {
+    pub fn new(width: usize, height: usize) -> Self {
+        Self {
+            width,
+            height,
+            columns: vec![],
+            rows: vec![],
+            topology: Topology::Square,
+        }
+    }
+
+    pub fn square(size: usize) -> Self {
+        Self::new(size, size)
+    }
+
+    pub fn with_columns(self, widths: impl IntoIterator<Item = usize>) -> Self {
+        Self {
+            columns: widths.into_iter().collect(),
+            ..self
+        }
+    }
+
+    pub fn with_rows(self, heights: impl IntoIterator<Item = usize>) -> Self {
+        Self {
+            rows: heights.into_iter().collect(),
+            ..self
+        }
+    }
+
+    pub fn with_topology(self, topology: Topology) -> Self {
+        Self { topology, ..self }
+    }
+
+    /// Position in pixels of the given coordinates, in cells. Columns and rows left of or above the grid have the default size.
+    pub fn coords(&self, (x, y): (f32, f32)) -> (f32, f32) {
+        match self.topology {
+            Topology::Square => (
+                offset(x, self.width, &self.columns),
+                offset(y, self.height, &self.rows),
+            ),
+            _ => self.scaled(self.topology.project((x, y))),
+        }
+    }
+
+    /// Outlines of the cells of the region, in pixels. See `Topology::outlines`.
+    pub fn outlines(&self, region: &Region) -> Vec<Vec<(f32, f32)>> {
+        match self.topology {
+            Topology::Square => {
+                let ((left, top), (right, bottom)) = self.bounds(region);
+                vec![vec![
+                    (left, top),
+                    (right, top),
+                    (right, bottom),
+                    (left, bottom),
+                ]]
+            }
+            _ => self
+                .topology
+                .outlines(region)
+                .into_iter()
+                .map(|outline| {
+                    outline
+                        .into_iter()
+                        .map(|coords| self.scaled(coords))
+                        .collect()
+                })
+                .collect(),
+        }
+    }
+
+    /// Top-left and bottom-right corners of the smallest rectangle containing the region's cells, in pixels
+    pub fn bounds(&self, region: &Region) -> ((f32, f32), (f32, f32)) {
+        if self.topology != Topology::Square {
+            let points: Vec<_> =
+                self.outlines(region).into_iter().flatten().collect();
+            let Some(&first) = points.first() else {
+                let start = self.coords(region.start.exact_xy());
+                return (start, start);
+            };
+
+            return points.into_iter().fold(
+                (first, first),
+                |((left, top), (right, bottom)), (x, y)| {
+                    ((left.min(x), top.min(y)), (right.max(x), bottom.max(y)))
+                },
+            );
+        }
+
+        let (end_x, end_y) = region.end.exact_xy();
+        let (left, top) = self.coords(region.start.exact_xy());
+        let (right, bottom) = self.coords((end_x + 1.0, end_y + 1.0));
+        ((left, top), (right.max(left), bottom.max(top)))
+    }
+
+    /// Width and height in pixels of the cell the coordinates are in
+    pub fn cell(&self, (x, y): (f32, f32)) -> (f32, f32) {
+        if self.topology != Topology::Square {
+            return (self.width as f32, self.height as f32);
+        }
+
+        (
+            track(x, self.width, &self.columns),
+            track(y, self.height, &self.rows),
+        )
+    }
+
+    fn scaled(&self, (x, y): (f32, f32)) -> (f32, f32) {
+        (x * self.width as f32, y * self.height as f32)
+    }
+
+    /// Length in pixels of a radius of `cells` cells, for a circle centered at the given coordinates.
+    /// Measured along the shortest side of the cell, so that circles stay round and fit in their cells.
+    pub fn radius(&self, cells: f32, around: (f32, f32)) -> f32 {
+        let (width, height) = self.cell(around);
+        cells * width.min(height)
+    }
+}
+
+impl Default for CellSize {
+    fn default() -> Self {
+        Self::square(50)
+    }
+}
+
+impl From<usize> for CellSize {
+    fn from(size: usize) -> Self {
+        Self::square(size)
+    }
+}
+
+impl From<(usize, usize)> for CellSize {
+    fn from((width, height): (usize, usize)) -> Self {
+        Self::new(width, height)
+    }
+}
+
+impl From<&CellSize> for CellSize {
+    fn from(cell_size: &CellSize) -> Self {
+        cell_size.clone()
+    }
+}
+
+/// Size of the column or row the position is in
+fn track(position: f32, default: usize, sizes: &[usize]) -> f32 {
+    let index = position.floor();
+    if index < 0.0 {
+        return default as f32;
+    }
+
+    *sizes.get(index as usize).unwrap_or(&default) as f32
+}
+
+/// Distance in pixels from the first column or row to the position
+fn offset(position: f32, default: usize, sizes: &[usize]) -> f32 {
+    if position < 0.0 || sizes.is_empty() {
+        return position * default as f32;
+    }
+
+    let index = position.floor() as usize;
+    let sized = index.min(sizes.len());
+    let before = sizes[..sized].iter().sum::<usize>() + (index - sized) * default;
+
+    before as f32 + position.fract() * track(position, default, sizes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::geometry::assert_close;
+
+    fn typographic() -> CellSize {
+        CellSize::new(20, 10).with_columns([40, 30]).with_rows([5])
+    }
+
+    #[test]
+    fn columns_and_rows_have_their_own_sizes() {
+        let cell_size = typographic();
+        assert_eq!(cell_size.cell((0.5, 0.5)), (40.0, 5.0));
+        assert_eq!(cell_size.cell((1.5, 1.5)), (30.0, 10.0));
+        assert_eq!(cell_size.cell((5.0, 3.0)), (20.0, 10.0));
+        assert_eq!(cell_size.cell((-1.0, -1.0)), (20.0, 10.0));
+    }
+
+    #[test]
+    fn coords_add_up_the_tracks_before() {
+        let cell_size = typographic();
+        assert_eq!(cell_size.coords((0.0, 0.0)), (0.0, 0.0));
+        assert_eq!(cell_size.coords((3.0, 2.0)), (90.0, 15.0));
+        assert_eq!(cell_size.coords((1.5, 0.5)), (55.0, 2.5));
+        assert_eq!(cell_size.coords((-2.0, -1.0)), (-40.0, -10.0));
+    }
+
+    #[test]
+    fn bounds_of_square_grids() {
+        let cell_size = typographic();
+        let region = Region::from(((1, 0), (2, 1)));
+        assert_eq!(cell_size.bounds(&region), ((40.0, 0.0), (90.0, 15.0)));
+        assert_eq!(
+            cell_size.outlines(&region),
+            vec![vec![(40.0, 0.0), (90.0, 0.0), (90.0, 15.0), (40.0, 15.0)]]
+        );
+    }
+
+    #[test]
+    fn bounds_of_hexagonal_grids() {
+        let cell_size = CellSize::square(10).with_topology(Topology::Hexagonal);
+        let first = cell_size.bounds(&Region::from(((0, 0), (0, 0))));
+        assert_close(first.0, (0.0, 0.0));
+        assert_close(first.1, (10.0, 10.0));
+
+        // odd rows are shifted right and overlap the previous one
+        let shifted = cell_size.bounds(&Region::from(((0, 1), (0, 1))));
+        assert_close(shifted.0, (5.0, 7.5));
+        assert_close(shifted.1, (15.0, 17.5));
+    }
+
+    #[test]
+    fn other_topologies_ignore_track_sizes() {
+        let cell_size = typographic().with_topology(Topology::Isometric);
+        assert_eq!(cell_size.cell((0.5, 0.5)), (20.0, 10.0));
+        assert_eq!(cell_size.coords((1.0, 1.0)), (0.0, 10.0));
+    }
+
+    #[test]
+    fn polar_coords_go_clockwise_from_the_top() {
+        let cell_size =
+            CellSize::square(10).with_topology(Topology::polar(4).unwrap());
+        assert_close(cell_size.coords((0.0, 1.0)), (0.0, -10.0));
+        assert_close(cell_size.coords((1.0, 2.0)), (20.0, 0.0));
+        assert_close(cell_size.coords((2.0, 1.0)), (0.0, 10.0));
+    }
+
+    #[test]
+    fn radius_fits_in_wide_cells() {
+        assert_eq!(CellSize::new(20, 10).radius(1.0, (0.0, 0.0)), 10.0);
+        assert_eq!(CellSize::new(10, 30).radius(0.5, (0.0, 0.0)), 5.0);
+
+        let cell_size = typographic();
+        assert_eq!(cell_size.radius(1.0, (0.5, 0.5)), 5.0);
+        assert_eq!(cell_size.radius(1.0, (1.5, 1.5)), 10.0);
+    }
+}
@@ -1,10 +1,35 @@
 pub mod angle;
 pub mod axis;
+pub mod cell_size;
 pub mod point;
 pub mod region;
+pub mod topology;
 
 pub use angle::Angle;
 pub use axis::Axis;
-pub use point::Point::{Center as CenterPoint, Corner as CornerPoint};
+pub use cell_size::CellSize;
+pub use point::Point::{
+    Center as CenterPoint, Corner as CornerPoint, Fractional as FractionalPoint,
+};
 pub use point::{Norm, Point};
 pub use region::{Containable, Region};
+pub use topology::Topology;
+
+/// Hashes floats by their bits. Both zeros hash the same, since they are equal.
+pub(crate) fn hash_floats<H: std::hash::Hasher>(values: &[f32], state: &mut H) {
+    for value in values {
+        state.write_u32((value + 0.0).to_bits());
+    }
+}
+
+/// Asserts that two coordinates are equal up to rounding errors.
+#[cfg(test)]
+pub(crate) fn assert_close(
+    (x, y): (f32, f32),
+    (expected_x, expected_y): (f32, f32),
+) {
+    assert!(
+        (x - expected_x).abs() < 1e-4 && (y - expected_y).abs() < 1e-4,
+        "({x}, {y}) is not ({expected_x}, {expected_y})"
+    );
+}
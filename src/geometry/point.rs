@@ -2,17 +2,32 @@ use num::FromPrimitive;
 use serde::{Deserialize, Serialize};
 
 use crate::{
-    Point::{Center, Corner},
+    CellSize,
+    Point::{Center, Corner, Fractional},
     Region,
 };
 
-use super::Angle;
+use super::{Angle, hash_floats};
 
 #[derive(Clone, Copy, PartialEq, Serialize, Deserialize)]
 #[cfg_attr(feature = "web", serde(tag = "type", content = "data"))]
 pub enum Point {
     Corner(usize, usize),
     Center(usize, usize),
+    /// Exact position, in cells, see `Point::at`. Can be negative, to place things off-canvas.
+    /// Unlike corner and center points, it is not tied to a cell: shapes drawn around a cell,
+    /// such as circles or centered text, are drawn around this point instead.
+    Fractional(f32, f32),
+}
+
+impl std::hash::Hash for Point {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        std::mem::discriminant(self).hash(state);
+        match self {
+            Corner(x, y) | Center(x, y) => (x, y).hash(state),
+            Fractional(x, y) => hash_floats(&[*x, *y], state),
+        }
+    }
 }
 
 impl Default for Point {
@@ -22,38 +37,65 @@ impl Default for Point {
 }
 
 impl Point {
-    pub fn coords(&self, cell_size: usize) -> (f32, f32) {
-        let (x, y) = self.xy::<f32>();
-        let cell = cell_size as f32;
+    pub fn coords(&self, cell_size: &CellSize) -> (f32, f32) {
+        cell_size.coords(self.grid_coords())
+    }
 
+    /// Coordinates in cells of where the point is drawn: center points are at the middle of their cell
+    pub fn grid_coords(&self) -> (f32, f32) {
+        let (x, y) = self.exact_xy();
         match self {
-            Point::Corner(..) => (x * cell, y * cell),
-            Point::Center(..) => (x * cell + cell / 2.0, y * cell + cell / 2.0),
+            Point::Center(..) => (x + 0.5, y + 0.5),
+            Point::Corner(..) | Point::Fractional(..) => (x, y),
+        }
+    }
+
+    /// Point at the given coordinates, in cells.
+    /// Gives a corner point when the coordinates are whole numbers, so that grid-aligned composition keeps working.
+    pub fn at(x: f32, y: f32) -> Self {
+        match (snapped(x), snapped(y)) {
+            (Some(x), Some(y)) => Point::Corner(x, y),
+            _ => Point::Fractional(x, y),
         }
     }
 
+    /// Same as `Point::at`, but gives a center point when the coordinates are at the center of a cell
+    pub fn centered_at(x: f32, y: f32) -> Self {
+        match (snapped(x - 0.5), snapped(y - 0.5)) {
+            (Some(x), Some(y)) => Point::Center(x, y),
+            _ => Point::Fractional(x, y),
+        }
+    }
+
+    /// Fractional points are left as is
     pub fn as_centered(&self) -> Self {
         match self {
             Point::Corner(x, y) => Point::Center(*x, *y),
-            Point::Center(_, _) => *self,
+            Point::Center(..) | Point::Fractional(..) => *self,
         }
     }
 
+    /// Fractional points are left as is
     pub fn as_corner(&self) -> Self {
         match self {
             Point::Center(x, y) => Point::Corner(*x, *y),
-            Point::Corner(_, _) => *self,
+            Point::Corner(..) | Point::Fractional(..) => *self,
         }
     }
 
     pub fn region(&self) -> Region {
-        Region::from((self.clone(), self.clone()))
+        Region::from((*self, *self))
     }
 
+    /// Moves the point to another cell. Fractional points keep their position within the cell.
     pub fn with(&self, x: usize, y: usize) -> Self {
         match self {
             Point::Corner(..) => Point::Corner(x, y),
             Point::Center(..) => Point::Center(x, y),
+            Point::Fractional(fx, fy) => Point::Fractional(
+                x as f32 + fx.rem_euclid(1.0),
+                y as f32 + fy.rem_euclid(1.0),
+            ),
         }
     }
 
@@ -62,11 +104,18 @@ impl Point {
     }
 
     pub fn set_x(&mut self, x: usize) {
-        self.set(x, self.y());
+        *self = self.with_x(x);
     }
 
+    /// Moves the point to another column. Its row, and its position within the cell, are kept.
     pub fn with_x(&self, x: usize) -> Self {
-        self.with(x, self.y())
+        match *self {
+            Point::Corner(_, y) => Point::Corner(x, y),
+            Point::Center(_, y) => Point::Center(x, y),
+            Point::Fractional(fx, fy) => {
+                Point::Fractional(x as f32 + fx.rem_euclid(1.0), fy)
+            }
+        }
     }
 
     pub fn increment_x(&mut self, by: isize) {
@@ -74,24 +123,48 @@ impl Point {
     }
 
     pub fn set_y(&mut self, y: usize) {
-        self.set(self.x(), y);
+        *self = self.with_y(y);
     }
 
     pub fn increment_y(&mut self, by: isize) {
         self.set_y(self.y().saturating_add_signed(by));
     }
 
+    /// Moves the point to another row. Its column, and its position within the cell, are kept.
     pub fn with_y(&self, y: usize) -> Self {
-        self.with(self.x(), y)
+        match *self {
+            Point::Corner(x, _) => Point::Corner(x, y),
+            Point::Center(x, _) => Point::Center(x, y),
+            Point::Fractional(fx, fy) => {
+                Point::Fractional(fx, y as f32 + fy.rem_euclid(1.0))
+            }
+        }
     }
 
+    /// Coordinates of the cell the point is in.
+    /// Cells left of or above the grid are clamped to 0, unless `N` is a signed type.
     pub fn xy<N: FromPrimitive>(&self) -> (N, N) {
-        let (x, y) = match self {
-            &Point::Corner(x, y) => (x, y),
-            &Point::Center(x, y) => (x, y),
-        };
+        match self {
+            &Point::Corner(x, y) | &Point::Center(x, y) => {
+                (N::from_usize(x).unwrap(), N::from_usize(y).unwrap())
+            }
+            &Point::Fractional(x, y) => {
+                let cell = |value: f32| {
+                    N::from_isize(value.floor() as isize)
+                        .unwrap_or_else(|| N::from_usize(0).unwrap())
+                };
+                (cell(x), cell(y))
+            }
+        }
+    }
 
-        (N::from_usize(x).unwrap(), N::from_usize(y).unwrap())
+    /// Coordinates in cells, including the fractional part of fractional points.
+    /// Unlike with `coords`, center points are not moved to the center of their cell.
+    pub fn exact_xy(&self) -> (f32, f32) {
+        match self {
+            Point::Fractional(x, y) => (*x, *y),
+            _ => self.xy(),
+        }
     }
 
     pub fn x(&self) -> usize {
@@ -102,29 +175,55 @@ impl Point {
         self.xy().1
     }
 
+    /// Going left of or above the grid gives a fractional point with negative coordinates, see `Point::nudged`
     pub fn translated(&self, dx: i32, dy: i32) -> Self {
-        Self::from((
-            (self.x() as i32 + dx) as usize,
-            (self.y() as i32 + dy) as usize,
-        ))
+        if let Point::Fractional(x, y) = self {
+            return Point::at(x + dx as f32, y + dy as f32);
+        }
+
+        let (x, y) = (self.x() as i32 + dx, self.y() as i32 + dy);
+        if x < 0 || y < 0 {
+            return self.nudged(dx as f32, dy as f32);
+        }
+
+        self.with(x as usize, y as usize)
     }
 
     pub fn translated_by(&self, point: Point) -> Self {
+        if let Point::Fractional(x, y) = self {
+            let (dx, dy) = point.exact_xy();
+            return Point::at(x + dx, y + dy);
+        }
+        if let Point::Fractional(dx, dy) = point {
+            return self.nudged(dx, dy);
+        }
+
         Self::from((self.x() + point.x(), self.y() + point.y()))
     }
 
     pub fn translate(&mut self, dx: i32, dy: i32) {
-        *self = Self::from((
-            (self.x() as i32 + dx) as usize,
-            (self.y() as i32 + dy) as usize,
-        ))
+        *self = self.translated(dx, dy);
+    }
+
+    /// Moves the point by a fraction of a cell. The resulting point is drawn at the same place as this one, moved by `dx` and `dy` cells.
+    pub fn nudged(&self, dx: f32, dy: f32) -> Self {
+        let (x, y) = self.grid_coords();
+        match self {
+            Point::Center(..) => Point::centered_at(x + dx, y + dy),
+            _ => Point::at(x + dx, y + dy),
+        }
+    }
+
+    pub fn nudge(&mut self, dx: f32, dy: f32) {
+        *self = self.nudged(dx, dy);
     }
 
     /// get SVG coordinates of the cell's center instead of its origin (top-left)
     #[deprecated = "Use a CenterPoint instead (WIP)"]
-    pub fn center_coords(&self, cell_size: usize) -> (f32, f32) {
+    pub fn center_coords(&self, cell_size: &CellSize) -> (f32, f32) {
         let (x, y) = self.coords(cell_size);
-        (x + cell_size as f32 / 2.0, y + cell_size as f32 / 2.0)
+        let (width, height) = cell_size.cell(self.grid_coords());
+        (x + width / 2.0, y + height / 2.0)
     }
 
     pub fn distance_to(&self, other: &Point) -> (usize, usize) {
@@ -134,23 +233,31 @@ impl Point {
         )
     }
 
+    /// Rotates the point around another one. The result is a fractional point, unless it lands exactly on the grid.
     pub fn rotated(&self, around: &Point, angle: Angle) -> Self {
-        let (dx, dy) = (
-            self.x() as f32 - around.x() as f32,
-            self.y() as f32 - around.y() as f32,
-        );
+        let (x, y) = self.grid_coords();
+        let (around_x, around_y) = around.grid_coords();
+        let (dx, dy) = (x - around_x, y - around_y);
 
         let (cos, sin) = angle.cos_sin();
         let new_x = dx * cos - dy * sin;
         let new_y = dx * sin + dy * cos;
 
-        Self::from((
-            (new_x + around.x() as f32) as usize,
-            (new_y + around.y() as f32) as usize,
-        ))
+        match self {
+            Point::Center(..) => {
+                Point::centered_at(new_x + around_x, new_y + around_y)
+            }
+            _ => Point::at(new_x + around_x, new_y + around_y),
+        }
     }
 }
 
+/// Whole number close enough to the value, if any. Absorbs rounding errors, e.g. after gliding or rotating.
+fn snapped(value: f32) -> Option<usize> {
+    let rounded = value.round();
+    ((value - rounded).abs() < 1e-4 && rounded >= 0.0).then_some(rounded as usize)
+}
+
 impl From<(usize, usize)> for Point {
     fn from(value: (usize, usize)) -> Self {
         Self::Corner(value.0, value.1)
@@ -163,25 +270,25 @@ impl From<(&usize, &usize)> for Point {
     }
 }
 
+/// Negative coordinates give a fractional point, off-canvas
 impl From<(i32, i32)> for Point {
-    fn from(value: (i32, i32)) -> Self {
-        Self::Corner(value.0 as usize, value.1 as usize)
+    fn from((x, y): (i32, i32)) -> Self {
+        Self::at(x as f32, y as f32)
     }
 }
 
 impl PartialEq<(usize, usize)> for Point {
     fn eq(&self, other: &(usize, usize)) -> bool {
-        self.xy() == other.clone()
+        self.xy() == *other
     }
 }
 
-impl Eq for Point {}
-
 impl std::fmt::Display for Point {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
         match self {
             Point::Corner(x, y) => write!(f, "({x}, {y})"),
             Point::Center(x, y) => write!(f, "[{x}, {y}]"),
+            Point::Fractional(x, y) => write!(f, "({x:?}, {y:?})"),
         }
     }
 }
@@ -191,6 +298,7 @@ impl std::fmt::Debug for Point {
         match self {
             Point::Corner(x, y) => write!(f, "({x}, {y})"),
             Point::Center(x, y) => write!(f, "[{x}, {y}]"),
+            Point::Fractional(x, y) => write!(f, "({x:?}, {y:?})"),
         }
     }
 }
@@ -198,10 +306,13 @@ impl std::fmt::Debug for Point {
 impl std::ops::Sub for Point {
     type Output = (isize, isize);
 
+    /// Offset between the cells the points are in. Fractional parts are dropped, compare `exact_xy` for exact offsets.
     fn sub(self, rhs: Point) -> Self::Output {
         match (self, rhs) {
             (Corner(..), Corner(..)) => {}
             (Center(..), Center(..)) => {}
+            // compared by the cells they're in
+            (Fractional(..), _) | (_, Fractional(..)) => {}
             _ => panic!("Cannot subtract CornerPoint and CenterPoint"),
         }
 
@@ -221,3 +332,68 @@ impl Norm for (usize, usize) {
         ((self.0 * self.0 + self.1 * self.1) as f32).sqrt()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fractional_points_keep_their_position_within_cells() {
+        assert_eq!(
+            Point::Fractional(1.25, 2.5).with(3, 4),
+            Point::Fractional(3.25, 4.5)
+        );
+        // -0.75 is a quarter of the way into cell -1
+        assert_eq!(
+            Point::Fractional(-0.75, -1.5).with(2, 2),
+            Point::Fractional(2.25, 2.5)
+        );
+    }
+
+    #[test]
+    fn translations_landing_on_corners_snap_to_the_grid() {
+        let point = Point::Fractional(1.5, 0.25);
+        assert_eq!(
+            point.translated_by(Point::Fractional(0.5, 0.75)),
+            Point::Corner(2, 1)
+        );
+        assert_eq!(point - Point::Corner(0, 0), (1, 0));
+    }
+
+    #[test]
+    fn setting_one_coordinate_keeps_the_other() {
+        let mut point = Point::Fractional(-2.5, -1.5);
+        point.set_x(3);
+        assert_eq!(point, Point::Fractional(3.5, -1.5));
+        point.set_y(1);
+        assert_eq!(point, Point::Fractional(3.5, 1.5));
+        assert_eq!(Point::Center(1, 2).with_x(4), Point::Center(4, 2));
+    }
+
+    #[test]
+    fn points_at_whole_coordinates_snap_to_the_grid() {
+        assert_eq!(Point::at(2.0, 3.0), Point::Corner(2, 3));
+        assert_eq!(Point::at(2.00001, 3.0), Point::Corner(2, 3));
+        assert_eq!(Point::at(-1.0, 3.0), Point::Fractional(-1.0, 3.0));
+        assert_eq!(Point::centered_at(1.5, 0.5), Point::Center(1, 0));
+    }
+
+    #[test]
+    fn translating_off_the_grid() {
+        let point = Point::Corner(1, 1).translated(-3, 0);
+        assert_eq!(point, Point::Fractional(-2.0, 1.0));
+        assert_eq!(point.xy::<isize>(), (-2, 1));
+        assert_eq!(point.xy::<usize>(), (0, 1));
+        // and back on it
+        assert_eq!(point.translated(3, 0), Point::Corner(1, 1));
+    }
+
+    #[test]
+    fn nudged_center_points_stay_centered() {
+        assert_eq!(Point::Center(1, 1).nudged(1.0, 0.0), Point::Center(2, 1));
+        assert_eq!(
+            Point::Center(1, 1).nudged(0.25, 0.0),
+            Point::Fractional(1.75, 1.5)
+        );
+    }
+}
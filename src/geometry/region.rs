@@ -1,4 +1,4 @@
-use crate::{Point, Shape};
+use crate::{CellSize, Point, Shape};
 use anyhow::{Error, Result, anyhow, format_err};
 #[cfg(feature = "web")]
 use wasm_bindgen::prelude::*;
@@ -6,7 +6,7 @@ use wasm_bindgen::prelude::*;
 use super::Axis;
 
 #[cfg_attr(feature = "web", wasm_bindgen)]
-#[derive(Debug, Clone, Default, Copy)]
+#[derive(Debug, Clone, Default, Copy, Hash)]
 pub struct Region {
     pub start: Point,
     pub end: Point,
@@ -27,11 +27,17 @@ impl Region {
     }
 
     pub fn iter_lower_triangle(&self) -> impl Iterator<Item = Point> {
-        self.iter().filter(|p| p.x() < p.y())
+        self.iter().filter(|p| {
+            let (x, y) = p.xy::<isize>();
+            x < y
+        })
     }
 
     pub fn iter_upper_strict_triangle(&self) -> impl Iterator<Item = Point> {
-        self.iter().filter(|p| p.x() >= p.y())
+        self.iter().filter(|p| {
+            let (x, y) = p.xy::<isize>();
+            x >= y
+        })
     }
 
     /// Iterates all points outlining the region, in clockwise order starting from top-left
@@ -43,23 +49,23 @@ impl Region {
     }
 
     pub fn top_edge(&self) -> impl DoubleEndedIterator<Item = Point> {
-        (self.start.x()..=self.end.x())
-            .map(move |x| Point::Corner(x, self.start.y()))
+        let ((_, start_y), _) = self.cells();
+        self.x_range().map(move |x| cell_corner(x, start_y))
     }
 
     pub fn bottom_edge(&self) -> impl DoubleEndedIterator<Item = Point> {
-        (self.start.x()..=self.end.x())
-            .map(move |x| Point::Corner(x, self.end.y()))
+        let (_, (_, end_y)) = self.cells();
+        self.x_range().map(move |x| cell_corner(x, end_y))
     }
 
     pub fn left_edge(&self) -> impl DoubleEndedIterator<Item = Point> {
-        (self.start.y()..=self.end.y())
-            .map(move |y| Point::Corner(self.start.x(), y))
+        let ((start_x, _), _) = self.cells();
+        self.y_range().map(move |y| cell_corner(start_x, y))
     }
 
     pub fn right_edge(&self) -> impl DoubleEndedIterator<Item = Point> {
-        (self.start.y()..=self.end.y())
-            .map(move |y| Point::Corner(self.end.x(), y))
+        let (_, (end_x, _)) = self.cells();
+        self.y_range().map(move |y| cell_corner(end_x, y))
     }
 
     /// Corners of the region's outline
@@ -91,17 +97,17 @@ impl Region {
         Shape::Rectangle(self.start, self.end)
     }
 
-    pub fn center_coords(&self, cell_size: usize) -> (f32, f32) {
-        let (x, y) = self.center().coords(cell_size);
-        let (h, w) = self.size(cell_size);
-
-        (x + (w / 2.0), y + (h / 2.0))
+    /// Middle of the region's bounds, in pixels
+    pub fn center_coords(&self, cell_size: &CellSize) -> (f32, f32) {
+        let ((left, top), (right, bottom)) = cell_size.bounds(self);
+        ((left + right) / 2.0, (top + bottom) / 2.0)
     }
 }
 
 pub struct RegionIterator {
     region: Region,
-    current: Point,
+    /// Cell the next point is in
+    current: (isize, isize),
 }
 
 impl IntoIterator for Region {
@@ -117,16 +123,24 @@ impl Iterator for RegionIterator {
     type Item = Point;
 
     fn next(&mut self) -> Option<Self::Item> {
-        if self.current.x() > self.region.end.x() {
-            self.current
-                .set(self.region.start.x(), self.current.y() + 1);
+        let ((start_x, _), (end_x, end_y)) = self.region.cells();
+        if self.current.0 > end_x {
+            self.current = (start_x, self.current.1 + 1);
         }
-        if self.current.y() > self.region.end.y() {
+        if self.current.0 > end_x || self.current.1 > end_y {
             return None;
         }
-        let result = self.current;
-        self.current.set_x(self.current.x() + 1);
-        Some(result)
+
+        let (x, y) = self.current;
+        self.current.0 += 1;
+        // points are of the same kind as the region's start, which can only be off-canvas when fractional
+        Some(match self.region.start {
+            Point::Fractional(fx, fy) => Point::at(
+                x as f32 + fx.rem_euclid(1.0),
+                y as f32 + fy.rem_euclid(1.0),
+            ),
+            start => start.with(x as usize, y as usize),
+        })
     }
 }
 
@@ -134,7 +148,7 @@ impl From<&Region> for RegionIterator {
     fn from(region: &Region) -> Self {
         Self {
             region: *region,
-            current: region.start,
+            current: region.start.xy(),
         }
     }
 }
@@ -164,10 +178,9 @@ impl std::ops::Sub for Region {
     type Output = (i32, i32);
 
     fn sub(self, rhs: Self) -> Self::Output {
-        (
-            (self.start.x() as i32 - rhs.start.x() as i32),
-            (self.start.y() as i32 - rhs.start.y() as i32),
-        )
+        let ((x, y), _) = self.cells();
+        let ((rhs_x, rhs_y), _) = rhs.cells();
+        ((x - rhs_x) as i32, (y - rhs_y) as i32)
     }
 }
 
@@ -193,25 +206,31 @@ impl Region {
     }
 
     pub fn bottomleft(&self) -> Point {
-        Point::Corner(self.start.x(), self.end.y())
+        let ((start_x, _), (_, end_y)) = self.cells();
+        cell_corner(start_x, end_y)
     }
 
     pub fn bottomright(&self) -> Point {
-        Point::Corner(self.end.x(), self.end.y())
+        let (_, (end_x, end_y)) = self.cells();
+        cell_corner(end_x, end_y)
     }
 
     pub fn topleft(&self) -> Point {
-        Point::Corner(self.start.x(), self.start.y())
+        let ((start_x, start_y), _) = self.cells();
+        cell_corner(start_x, start_y)
     }
 
     pub fn topright(&self) -> Point {
-        Point::Corner(self.end.x(), self.start.y())
+        let ((_, start_y), (end_x, _)) = self.cells();
+        cell_corner(end_x, start_y)
     }
 
+    /// Center of the middle cell, rounding towards the top-left
     pub fn center(&self) -> Point {
-        Point::Center(
-            (self.start.x() + self.end.x()) / 2,
-            (self.start.y() + self.end.y()) / 2,
+        let ((start_x, start_y), (end_x, end_y)) = self.cells();
+        cell_center(
+            (start_x + end_x).div_euclid(2),
+            (start_y + end_y).div_euclid(2),
         )
     }
 
@@ -220,15 +239,35 @@ impl Region {
     }
 
     pub fn merge<'a>(&'a self, other: &'a Region) -> Region {
+        let ((x1, y1), (x2, y2)) = self.cells();
+        let ((other_x1, other_y1), (other_x2, other_y2)) = other.cells();
+
         Region {
-            start: Point::Corner(
-                self.start.x().min(other.start.x()),
-                self.start.y().min(other.start.y()),
-            ),
-            end: Point::Corner(
-                self.end.x().max(other.end.x()),
-                self.end.y().max(other.end.y()),
-            ),
+            start: cell_corner(x1.min(other_x1), y1.min(other_y1)),
+            end: cell_corner(x2.max(other_x2), y2.max(other_y2)),
+        }
+    }
+
+    /// Coordinates of the first and last cells of the region, which can be negative when it is off-canvas
+    pub fn cells(&self) -> ((isize, isize), (isize, isize)) {
+        (self.start.xy(), self.end.xy())
+    }
+
+    /// Smallest region containing every cell touched by the given extent.
+    /// Coordinates are in grid units, as returned by `Point::coords(1)`.
+    pub fn covering(
+        (min_x, min_y): (f32, f32),
+        (max_x, max_y): (f32, f32),
+    ) -> Self {
+        let start = (min_x.floor() as isize, min_y.floor() as isize);
+        let end = (
+            (max_x.ceil() as isize - 1).max(start.0),
+            (max_y.ceil() as isize - 1).max(start.1),
+        );
+
+        Region {
+            start: cell_corner(start.0, start.1),
+            end: cell_corner(end.0, end.1),
         }
     }
 
@@ -284,16 +323,18 @@ impl Region {
         center: Point,
         (w, h): (usize, usize),
     ) -> Result<Self> {
-        let (half_w, half_h) = (w / 2, h / 2);
+        let (x, y) = center.xy::<isize>();
+        let (half_w, half_h) = ((w / 2) as isize, (h / 2) as isize);
         Self::new(
-            (center.x() - half_w, center.y() - half_h),
-            (center.x() + half_w, center.y() + half_h),
+            cell_corner(x - half_w, y - half_h),
+            cell_corner(x + half_w, y + half_h),
         )
     }
 
     // panics if the region is invalid
     pub fn ensure_valid(self) -> Result<Self> {
-        if self.start.x() > self.end.x() || self.start.y() > self.end.y() {
+        let ((start_x, start_y), (end_x, end_y)) = self.cells();
+        if start_x > end_x || start_y > end_y {
             return Err(format_err!(
                 "Invalid region: start ({:?}) > end ({:?})",
                 self.start,
@@ -302,7 +343,7 @@ impl Region {
         }
 
         // check that no point's coordinate is too close to usize::MAX
-        if vec![self.start.x(), self.start.y(), self.end.x(), self.end.y()]
+        if [self.start.x(), self.start.y(), self.end.x(), self.end.y()]
             .iter()
             .any(|&coord| coord >= usize::MAX - 10)
         {
@@ -319,21 +360,26 @@ impl Region {
         *self = self.translated(dx, dy);
     }
 
+    /// Regions can be moved off-canvas, see `Point::translated`
     pub fn translated(&self, dx: i32, dy: i32) -> Self {
         Self {
-            start: (
-                (self.start.x() as i32 + dx).max(0) as usize,
-                (self.start.y() as i32 + dy).max(0) as usize,
-            )
-                .into(),
-            end: (
-                (self.end.x() as i32 + dx).max(0) as usize,
-                (self.end.y() as i32 + dy).max(0) as usize,
-            )
-                .into(),
+            start: self.start.translated(dx, dy),
+            end: self.end.translated(dx, dy),
         }
     }
 
+    /// Moves the region by a fraction of a cell, see `Point::nudged`
+    pub fn nudged(&self, dx: f32, dy: f32) -> Self {
+        Self {
+            start: self.start.nudged(dx, dy),
+            end: self.end.nudged(dx, dy),
+        }
+    }
+
+    pub fn nudge(&mut self, dx: f32, dy: f32) {
+        *self = self.nudged(dx, dy);
+    }
+
     pub fn translated_by(&self, point: Point) -> Self {
         let (x, y) = point.xy::<i32>();
         self.translated(x, y)
@@ -343,11 +389,7 @@ impl Region {
     pub fn enlarged(&self, add_x: i32, add_y: i32) -> Self {
         let resulting = Self {
             start: self.start,
-            end: (
-                (self.end.x().saturating_add_signed(add_x as _)),
-                (self.end.y().saturating_add_signed(add_y as _)),
-            )
-                .into(),
+            end: self.end.translated(add_x, add_y),
         };
 
         resulting
@@ -366,75 +408,71 @@ impl Region {
             .enlarged(add_x, add_y)
     }
 
-    pub fn x_range(&self) -> std::ops::RangeInclusive<usize> {
-        self.start.x()..=self.end.x()
+    /// Columns of the region's cells, which can be negative, see `cells`
+    pub fn x_range(&self) -> std::ops::RangeInclusive<isize> {
+        let ((start_x, _), (end_x, _)) = self.cells();
+        start_x..=end_x
     }
-    pub fn y_range(&self) -> std::ops::RangeInclusive<usize> {
-        self.start.y()..=self.end.y()
+
+    /// Rows of the region's cells, which can be negative, see `cells`
+    pub fn y_range(&self) -> std::ops::RangeInclusive<isize> {
+        let ((_, start_y), (_, end_y)) = self.cells();
+        start_y..=end_y
     }
 
-    pub fn x_range_without_last(&self) -> std::ops::Range<usize> {
-        self.start.x()..self.end.x()
+    pub fn x_range_without_last(&self) -> std::ops::Range<isize> {
+        let ((start_x, _), (end_x, _)) = self.cells();
+        start_x..end_x
     }
 
-    pub fn y_range_without_last(&self) -> std::ops::Range<usize> {
-        self.start.y()..self.end.y()
+    pub fn y_range_without_last(&self) -> std::ops::Range<isize> {
+        let ((_, start_y), (_, end_y)) = self.cells();
+        start_y..end_y
     }
 
     pub fn within(&self, other: &Region) -> bool {
-        self.start.x() >= other.start.x()
-            && self.start.y() >= other.start.y()
-            && self.end.x() <= other.end.x()
-            && self.end.y() <= other.end.y()
+        let ((x1, y1), (x2, y2)) = self.cells();
+        let ((other_x1, other_y1), (other_x2, other_y2)) = other.cells();
+
+        x1 >= other_x1 && y1 >= other_y1 && x2 <= other_x2 && y2 <= other_y2
+    }
+
+    /// Whether the two regions share at least one cell
+    pub fn intersects(&self, other: &Region) -> bool {
+        let ((x1, y1), (x2, y2)) = self.cells();
+        let ((other_x1, other_y1), (other_x2, other_y2)) = other.cells();
+
+        x1 <= other_x2 && other_x1 <= x2 && y1 <= other_y2 && other_y1 <= y2
     }
 
     pub fn clamped(&self, within: &Region) -> Region {
+        let ((x1, y1), (x2, y2)) = self.cells();
+        let ((within_x1, within_y1), (within_x2, within_y2)) = within.cells();
+
         Region {
-            start: (
-                self.start.x().max(within.start.x()),
-                self.start.y().max(within.start.y()),
-            )
-                .into(),
-            end: (
-                self.end.x().min(within.end.x()),
-                self.end.y().min(within.end.y()),
-            )
-                .into(),
+            start: cell_corner(x1.max(within_x1), y1.max(within_y1)),
+            end: cell_corner(x2.min(within_x2), y2.min(within_y2)),
         }
     }
 
     pub fn width(&self) -> usize {
-        if self.end.x() < self.start.x() {
-            return 0;
-        }
-
-        self.end.x() - self.start.x() + 1
+        let ((start_x, _), (end_x, _)) = self.cells();
+        (end_x - start_x + 1).max(0) as usize
     }
 
     pub fn height(&self) -> usize {
-        let (sy, ey) = (self.start.y(), self.end.y());
-
-        if ey < sy {
-            return 0;
-        }
-
-        ey.checked_sub(sy)
-            .expect(&format!("{self:?} overflows when computing height"))
-            .checked_add(1)
-            .expect(&format!(
-                "{self:?} overflows when adjusting height computation"
-            ))
+        let ((_, start_y), (_, end_y)) = self.cells();
+        (end_y - start_y + 1).max(0) as usize
     }
 
     pub fn dimensions(&self) -> (usize, usize) {
         (self.width(), self.height())
     }
 
-    pub fn size(&self, cell_size: usize) -> (f32, f32) {
-        (
-            (self.width() * cell_size) as f32,
-            (self.height() * cell_size) as f32,
-        )
+    /// Size of the region, in pixels. Unlike `width` and `height`, takes fractional points into account.
+    pub fn size(&self, cell_size: impl Into<CellSize>) -> (f32, f32) {
+        let ((left, top), (right, bottom)) = cell_size.into().bounds(self);
+        (right - left, bottom - top)
     }
 
     // goes from -width to width (inclusive on both ends)
@@ -449,27 +487,35 @@ impl Region {
     }
 
     pub fn split(&self, along: Axis) -> (Region, Region) {
+        let ((start_x, start_y), (end_x, end_y)) = self.cells();
+        // both halves share the middle row or column
         match along {
-            Axis::Horizontal => (
-                Region {
-                    start: self.start,
-                    end: Point::Corner(self.end.x(), self.end.y() / 2),
-                },
-                Region {
-                    start: Point::Corner(self.start.x(), self.end.y() / 2),
-                    end: self.end,
-                },
-            ),
-            Axis::Vertical => (
-                Region {
-                    start: self.start,
-                    end: Point::Corner(self.end.x() / 2, self.end.y()),
-                },
-                Region {
-                    start: Point::Corner(self.end.x() / 2, self.start.y()),
-                    end: self.end,
-                },
-            ),
+            Axis::Horizontal => {
+                let middle = (start_y + end_y).div_euclid(2);
+                (
+                    Region {
+                        start: self.start,
+                        end: cell_corner(end_x, middle),
+                    },
+                    Region {
+                        start: cell_corner(start_x, middle),
+                        end: self.end,
+                    },
+                )
+            }
+            Axis::Vertical => {
+                let middle = (start_x + end_x).div_euclid(2);
+                (
+                    Region {
+                        start: self.start,
+                        end: cell_corner(middle, end_y),
+                    },
+                    Region {
+                        start: cell_corner(middle, start_y),
+                        end: self.end,
+                    },
+                )
+            }
         }
     }
 }
@@ -480,12 +526,138 @@ pub trait Containable<T> {
 
 impl Containable<Point> for Region {
     fn contains(&self, value: &Point) -> bool {
-        self.x_range().contains(&value.x()) && self.y_range().contains(&value.y())
+        let ((x1, y1), (x2, y2)) = self.cells();
+        let (x, y) = value.xy::<isize>();
+
+        (x1..=x2).contains(&x) && (y1..=y2).contains(&y)
     }
 }
 
+/// Corner of the cell, fractional if it is off-canvas
+pub(crate) fn cell_corner(x: isize, y: isize) -> Point {
+    Point::at(x as f32, y as f32)
+}
+
+/// Center of the cell, fractional if it is off-canvas
+pub(crate) fn cell_center(x: isize, y: isize) -> Point {
+    Point::centered_at(x as f32 + 0.5, y as f32 + 0.5)
+}
+
 impl std::fmt::Display for Region {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "[{},{}]", self.start, self.end)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Region of the cells from (-2, -1) to (1, 0)
+    fn off_canvas() -> Region {
+        Region::covering((-2.0, -1.0), (2.0, 1.0))
+    }
+
+    #[test]
+    fn negative_points_from_integers() {
+        assert_eq!(Point::from((2, 3)), Point::Corner(2, 3));
+        assert_eq!(Point::from((-2, 3)), Point::Fractional(-2.0, 3.0));
+    }
+
+    #[test]
+    fn iterating_over_negative_regions() {
+        let cells: Vec<_> = off_canvas()
+            .iter()
+            .map(|point| point.xy::<isize>())
+            .collect();
+        assert_eq!(
+            cells,
+            vec![
+                (-2, -1),
+                (-1, -1),
+                (0, -1),
+                (1, -1),
+                (-2, 0),
+                (-1, 0),
+                (0, 0),
+                (1, 0),
+            ]
+        );
+        // back on the grid
+        assert_eq!(off_canvas().iter().nth(6), Some(Point::Corner(0, 0)));
+    }
+
+    #[test]
+    fn negative_region_ranges_and_corners() {
+        let region = off_canvas();
+        assert_eq!(region.x_range(), -2..=1);
+        assert_eq!(region.y_range(), -1..=0);
+        assert_eq!(region.topleft(), Point::Fractional(-2.0, -1.0));
+        assert_eq!(region.bottomright(), Point::Corner(1, 0));
+        assert_eq!(region.center(), Point::Fractional(-0.5, -0.5));
+        assert_eq!(Region::from(((0, 0), (2, 2))).center(), Point::Center(1, 1));
+    }
+
+    #[test]
+    fn negative_region_edges() {
+        let outline: Vec<_> = off_canvas()
+            .outline()
+            .map(|point| point.xy::<isize>())
+            .collect();
+        assert_eq!(outline.len(), 9);
+        assert_eq!(outline[0], (-2, -1));
+        assert_eq!(outline[3], (1, -1));
+        assert_eq!(outline[4], (1, 0));
+    }
+
+    #[test]
+    fn clamping_negative_regions() {
+        let clamped = off_canvas().clamped(&Region::from(((0, 0), (3, 3))));
+        assert_eq!(clamped.cells(), ((0, 0), (1, 0)));
+
+        let within = Region::covering((-1.0, -1.0), (1.0, 1.0));
+        assert_eq!(off_canvas().clamped(&within).cells(), ((-1, -1), (0, 0)));
+    }
+
+    #[test]
+    fn center_coords_with_rectangular_cells() {
+        let cell_size = CellSize::new(20, 10);
+        let region = Region::from(((0, 0), (0, 0)));
+        assert_eq!(region.center_coords(&cell_size), (10.0, 5.0));
+        let region = Region::from(((1, 0), (2, 3)));
+        assert_eq!(region.center_coords(&cell_size), (40.0, 20.0));
+    }
+
+    #[test]
+    fn splitting_negative_regions() {
+        let region = Region::covering((-5.0, -5.0), (-1.0, -1.0));
+        for axis in [Axis::Vertical, Axis::Horizontal] {
+            let (first, second) = region.split(axis);
+            assert!(first.ensure_valid().is_ok());
+            assert!(second.ensure_valid().is_ok());
+            let cells = |region: Region| {
+                region
+                    .iter()
+                    .map(|point| point.xy::<isize>())
+                    .collect::<Vec<_>>()
+            };
+            let mut halves = [cells(first), cells(second)].concat();
+            halves.sort();
+            halves.dedup();
+            let mut all = cells(region);
+            all.sort();
+            assert_eq!(halves, all);
+        }
+
+        let (left, right) = region.split(Axis::Vertical);
+        assert_eq!(left.cells(), ((-5, -5), (-4, -2)));
+        assert_eq!(right.cells(), ((-4, -5), (-2, -2)));
+    }
+
+    #[test]
+    fn subtracting_negative_regions() {
+        let region = Region::from(((1, 1), (2, 2)));
+        assert_eq!(off_canvas() - region, (-3, -2));
+        assert_eq!(region.translated(-3, -2) - region, (-3, -2));
+    }
+}
@@ -0,0 +1,329 @@
+use anyhow::{Result, anyhow};
+
+use crate::{Angle, Point, Region};
+
+/// Segments used to draw one cell's worth of a ring on polar grids
+const ARC_STEPS: usize = 8;
+
+/// How the cells of the grid are laid out. Points and regions keep using column and row coordinates,
+/// which the topology maps to a position on the canvas.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Topology {
+    /// Square cells, in columns and rows
+    #[default]
+    Square,
+    /// Pointy-top hexagons, in rows. Odd rows are shifted right by half a cell, and rows overlap by a quarter of a cell.
+    /// Cell width and height are the hexagon's: make the height `2 / √3` times the width for regular hexagons.
+    Hexagonal,
+    /// Diamonds of the isometric projection of a square grid: columns go down and right, rows go down and left.
+    /// The cell width and height are the diamond's, usually twice as wide as tall.
+    Isometric,
+    /// Rings around the origin, divided in `sectors`. Columns are sectors, going clockwise from the top, and rows are rings, going outwards.
+    /// Columns past the last sector wrap around. `hole` is the radius of the empty center, in rings.
+    /// The cell height is the width of a ring. Without any sector, the grid has no cells.
+    Polar { sectors: usize, hole: f32 },
+}
+
+impl Topology {
+    /// Polar grid without a hole. Fails if `sectors` is 0.
+    pub fn polar(sectors: usize) -> Result<Self> {
+        if sectors == 0 {
+            return Err(anyhow!("Polar grids need at least one sector"));
+        }
+        Ok(Self::Polar { sectors, hole: 0.0 })
+    }
+
+    /// Position in cells of the given column and row coordinates, as if cells were squares of size 1
+    pub fn project(&self, (x, y): (f32, f32)) -> (f32, f32) {
+        match self {
+            Topology::Square => (x, y),
+            Topology::Hexagonal => {
+                let row = y.floor();
+                let shift = if row.rem_euclid(2.0) == 1.0 { 0.5 } else { 0.0 };
+                (x + shift, row * 0.75 + (y - row))
+            }
+            Topology::Isometric => ((x - y) / 2.0, (x + y) / 2.0),
+            Topology::Polar { sectors, hole } => {
+                // sectors start at the top instead of the right
+                let angle = Angle::from_ratio(x, (*sectors).max(1) as f32)
+                    .degrees()
+                    - 90.0;
+                let (cos, sin) = Angle::from_degrees(angle).cos_sin();
+                let radius = hole + y;
+                (radius * cos, radius * sin)
+            }
+        }
+    }
+
+    /// Outlines of the cells of the region, as if cells were squares of size 1.
+    /// A single outline for regions that stay in one piece once laid out, or one per cell on hexagonal grids.
+    pub fn outlines(&self, region: &Region) -> Vec<Vec<(f32, f32)>> {
+        let (start_x, start_y) = region.start.exact_xy();
+        let (end_x, end_y) = region.end.exact_xy();
+        let (end_x, end_y) = (end_x + 1.0, end_y + 1.0);
+
+        match self {
+            Topology::Hexagonal => region
+                .iter()
+                .map(|cell| {
+                    let (x, y) = self.project(cell.as_centered().grid_coords());
+                    vec![
+                        (x, y - 0.5),
+                        (x + 0.5, y - 0.25),
+                        (x + 0.5, y + 0.25),
+                        (x, y + 0.5),
+                        (x - 0.5, y + 0.25),
+                        (x - 0.5, y - 0.25),
+                    ]
+                })
+                .collect(),
+            _ => {
+                // rings are curved: follow them more closely than straight edges
+                let steps = match self {
+                    Topology::Polar { .. } => {
+                        ((end_x - start_x).ceil() as usize * ARC_STEPS).max(1)
+                    }
+                    _ => 1,
+                };
+                let along = |from: f32, to: f32, y: f32| {
+                    (0..steps).map(move |i| {
+                        (from + (to - from) * i as f32 / steps as f32, y)
+                    })
+                };
+
+                vec![
+                    along(start_x, end_x, start_y)
+                        .chain([(end_x, start_y)])
+                        .chain(along(end_x, start_x, end_y))
+                        .chain([(start_x, end_y)])
+                        .map(|coords| self.project(coords))
+                        .collect(),
+                ]
+            }
+        }
+    }
+
+    /// Cells sharing an edge or a corner with the cell the point is in.
+    /// Cells left of or above the grid are not included.
+    pub fn neighbours(&self, point: Point) -> Vec<Point> {
+        let (x, y) = point.xy::<isize>();
+        let offsets: &[(isize, isize)] = match self {
+            Topology::Hexagonal if y.rem_euclid(2) == 0 => {
+                &[(-1, -1), (0, -1), (-1, 0), (1, 0), (-1, 1), (0, 1)]
+            }
+            Topology::Hexagonal => {
+                &[(0, -1), (1, -1), (-1, 0), (1, 0), (0, 1), (1, 1)]
+            }
+            Topology::Square | Topology::Isometric | Topology::Polar { .. } => &[
+                (-1, -1),
+                (0, -1),
+                (1, -1),
+                (-1, 0),
+                (1, 0),
+                (-1, 1),
+                (0, 1),
+                (1, 1),
+            ],
+        };
+
+        let mut neighbours: Vec<Point> = vec![];
+        for (dx, dy) in offsets {
+            if let Some(cell) = self.cell(x + dx, y + dy)
+                && cell != point.as_corner()
+                && !neighbours.contains(&cell)
+            {
+                neighbours.push(cell);
+            }
+        }
+        neighbours
+    }
+
+    /// Number of steps between the cells the points are in, going from neighbour to neighbour
+    pub fn distance(&self, from: Point, to: Point) -> usize {
+        let ((x1, y1), (x2, y2)) = (from.xy::<isize>(), to.xy::<isize>());
+
+        match self {
+            Topology::Square | Topology::Isometric => {
+                x1.abs_diff(x2).max(y1.abs_diff(y2))
+            }
+            Topology::Hexagonal => {
+                // axial coordinates of the hexagons
+                let q = |x: isize, y: isize| x - (y - y.rem_euclid(2)) / 2;
+                let (dq, dr) = (q(x2, y2) - q(x1, y1), y2 - y1);
+                (dq.unsigned_abs() + dr.unsigned_abs() + (dq + dr).unsigned_abs())
+                    / 2
+            }
+            Topology::Polar { sectors: 0, .. } => 0,
+            Topology::Polar { sectors, .. } => {
+                let dx = x1
+                    .rem_euclid(*sectors as isize)
+                    .abs_diff(x2.rem_euclid(*sectors as isize));
+                dx.min(sectors - dx).max(y1.abs_diff(y2))
+            }
+        }
+    }
+
+    /// Cells at most `radius` steps away from the point's, in row-major order.
+    /// A hexagon on hexagonal grids, and a band of rings and sectors on polar grids.
+    pub fn area(&self, center: Point, radius: usize) -> Vec<Point> {
+        let (x, y) = center.xy::<isize>();
+        let radius = radius as isize;
+
+        let mut cells: Vec<Point> = vec![];
+        for cell_y in y - radius..=y + radius {
+            for cell_x in x - radius..=x + radius {
+                if let Some(cell) = self.cell(cell_x, cell_y)
+                    && self.distance(center, cell) <= radius as usize
+                    && !cells.contains(&cell)
+                {
+                    cells.push(cell);
+                }
+            }
+        }
+        cells
+    }
+
+    /// Cells of the region. On polar grids, columns past the last sector wrap around to the first ones.
+    pub fn cells(&self, region: &Region) -> Vec<Point> {
+        let ((start_x, start_y), (end_x, end_y)) = region.cells();
+
+        let mut cells: Vec<Point> = vec![];
+        for y in start_y..=end_y {
+            for x in start_x..=end_x {
+                if let Some(cell) = self.cell(x, y)
+                    && !cells.contains(&cell)
+                {
+                    cells.push(cell);
+                }
+            }
+        }
+        cells
+    }
+
+    /// Cell at the given coordinates, if it is on the grid
+    fn cell(&self, x: isize, y: isize) -> Option<Point> {
+        let x = match self {
+            Topology::Polar { sectors: 0, .. } => return None,
+            Topology::Polar { sectors, .. } => x.rem_euclid(*sectors as isize),
+            _ => x,
+        };
+
+        (x >= 0 && y >= 0).then_some(Point::Corner(x as usize, y as usize))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::geometry::assert_close;
+
+    #[test]
+    fn polar_grids_need_sectors() {
+        assert!(Topology::polar(0).is_err());
+
+        let empty = Topology::Polar {
+            sectors: 0,
+            hole: 0.0,
+        };
+        assert!(empty.neighbours(Point::Corner(0, 0)).is_empty());
+        assert!(empty.cells(&Region::from(((0, 0), (2, 2)))).is_empty());
+        assert_eq!(empty.distance(Point::Corner(0, 0), Point::Corner(2, 3)), 0);
+        assert!(empty.project((1.0, 1.0)).0.is_finite());
+    }
+
+    #[test]
+    fn projections() {
+        assert_eq!(Topology::Square.project((2.0, 3.0)), (2.0, 3.0));
+        assert_eq!(Topology::Isometric.project((1.0, 0.0)), (0.5, 0.5));
+        assert_eq!(Topology::Isometric.project((0.0, 1.0)), (-0.5, 0.5));
+        assert_eq!(Topology::Hexagonal.project((0.0, 1.0)), (0.5, 0.75));
+        assert_close(
+            Topology::Polar {
+                sectors: 4,
+                hole: 1.0,
+            }
+            .project((0.0, 0.0)),
+            (0.0, -1.0),
+        );
+    }
+
+    #[test]
+    fn square_neighbours_stay_on_the_grid() {
+        assert_eq!(
+            Topology::Square.neighbours(Point::Corner(0, 0)),
+            vec![
+                Point::Corner(1, 0),
+                Point::Corner(0, 1),
+                Point::Corner(1, 1)
+            ]
+        );
+        assert_eq!(Topology::Square.neighbours(Point::Corner(2, 2)).len(), 8);
+    }
+
+    #[test]
+    fn hexagonal_neighbours_depend_on_the_row() {
+        assert_eq!(
+            Topology::Hexagonal.neighbours(Point::Corner(1, 1)),
+            vec![
+                Point::Corner(1, 0),
+                Point::Corner(2, 0),
+                Point::Corner(0, 1),
+                Point::Corner(2, 1),
+                Point::Corner(1, 2),
+                Point::Corner(2, 2),
+            ]
+        );
+        assert_eq!(
+            Topology::Hexagonal
+                .distance(Point::Corner(0, 0), Point::Corner(0, 1)),
+            1
+        );
+        assert_eq!(
+            Topology::Hexagonal
+                .distance(Point::Corner(0, 0), Point::Corner(1, 1)),
+            2
+        );
+        assert_eq!(Topology::Hexagonal.area(Point::Corner(1, 1), 1).len(), 7);
+    }
+
+    #[test]
+    fn polar_sectors_wrap_around() {
+        let polar = Topology::polar(4).unwrap();
+        assert_eq!(
+            polar.neighbours(Point::Corner(0, 0)),
+            vec![
+                Point::Corner(3, 0),
+                Point::Corner(1, 0),
+                Point::Corner(3, 1),
+                Point::Corner(0, 1),
+                Point::Corner(1, 1),
+            ]
+        );
+        assert_eq!(polar.distance(Point::Corner(0, 0), Point::Corner(3, 0)), 1);
+        assert_eq!(polar.distance(Point::Corner(0, 0), Point::Corner(2, 3)), 3);
+        assert_eq!(
+            polar.cells(&Region::from(((3, 0), (4, 0)))),
+            vec![Point::Corner(3, 0), Point::Corner(0, 0)]
+        );
+        assert_eq!(
+            Topology::polar(2)
+                .unwrap()
+                .cells(&Region::from(((0, 0), (3, 0)))),
+            vec![Point::Corner(0, 0), Point::Corner(1, 0)]
+        );
+    }
+
+    #[test]
+    fn square_areas() {
+        assert_eq!(Topology::Square.area(Point::Corner(1, 1), 1).len(), 9);
+        assert_eq!(
+            Topology::Square.area(Point::Corner(0, 0), 1),
+            vec![
+                Point::Corner(0, 0),
+                Point::Corner(1, 0),
+                Point::Corner(0, 1),
+                Point::Corner(1, 1),
+            ]
+        );
+    }
+}
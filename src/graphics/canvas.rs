@@ -1,13 +1,14 @@
 use anyhow::{Result, anyhow};
 use core::panic;
 use resvg::usvg;
-use std::{collections::HashMap, ops::Range, sync::Arc};
+use std::{ops::Range, sync::Arc};
 
 use itertools::Itertools as _;
 use measure_time::debug_time;
 
 use crate::{
-    Color, ColorMapping, Fill, Filter, Layer, ObjectSizes, Point, Region, Shape,
+    CellSize, Clip, Color, ColorMapping, Fill, Filter, Layer, Mask, ObjectSizes,
+    Point, Region, Shape, Topology,
     fonts::{FontOptions, load_fonts},
 };
 
@@ -16,9 +17,12 @@ use super::Object;
 #[derive(Debug, Clone)]
 pub struct Canvas {
     pub grid_size: (usize, usize),
-    pub cell_size: usize,
+    pub cell_size: CellSize,
     pub objects_count_range: Range<usize>,
     pub polygon_vertices_range: Range<usize>,
+    /// Whether randomly generated objects of a layer may overlap each other.
+    /// When disabled, objects that can't be placed without overlapping are left out.
+    pub random_objects_can_overlap: bool,
     pub outer_padding: usize,
     pub object_sizes: ObjectSizes,
     pub font_options: FontOptions,
@@ -46,9 +50,10 @@ impl Canvas {
         Self {
             name: String::new(),
             grid_size: (3, 3),
-            cell_size: 50,
+            cell_size: CellSize::default(),
             objects_count_range: 3..7,
             polygon_vertices_range: 2..7,
+            random_objects_can_overlap: true,
             outer_padding: 10,
             object_sizes: ObjectSizes::default(),
             font_options: FontOptions::default(),
@@ -78,15 +83,7 @@ impl Canvas {
     }
 
     pub fn init_layers(&mut self, names: Vec<&str>) {
-        self.layers = names
-            .iter()
-            .map(|name| Layer {
-                object_sizes: ObjectSizes::default(),
-                objects: HashMap::new(),
-                name: name.to_string(),
-                hidden: false,
-            })
-            .collect();
+        self.layers = names.iter().map(Layer::new).collect();
         if !self.layer_exists("root") {
             self.layers.push(Layer::new("root"));
         }
@@ -100,11 +97,64 @@ impl Canvas {
         };
     }
 
-    pub fn layer(&mut self, name: &str) -> Result<&mut Layer> {
-        self.layers
+    /// Sets the size of cells: a single size for square cells, a `(width, height)` pair, or a `CellSize` with columns and rows of their own sizes
+    pub fn set_cell_size(&mut self, cell_size: impl Into<CellSize>) {
+        self.cell_size = cell_size.into();
+    }
+
+    /// Lays out the grid's cells differently, see `Topology`. Points and regions are still given in columns and rows.
+    pub fn set_topology(&mut self, topology: Topology) {
+        self.cell_size.topology = topology;
+    }
+
+    pub fn topology(&self) -> Topology {
+        self.cell_size.topology
+    }
+
+    /// Layer with that name, or group nested in a layer with a path such as `"hud/left/meter"`
+    pub fn layer(&mut self, path: &str) -> Result<&mut Layer> {
+        let (name, groups) = match path.split_once('/') {
+            Some((name, groups)) => (name, Some(groups)),
+            None => (path, None),
+        };
+
+        let layer = self
+            .layers
             .iter_mut()
             .find(|layer| layer.name == name)
-            .ok_or(anyhow!("Layer {name} does not exist"))
+            .ok_or(anyhow!("Layer {name} does not exist"))?;
+
+        match groups {
+            Some(groups) => layer.group(groups),
+            None => Ok(layer),
+        }
+    }
+
+    pub fn find_layer(&self, path: &str) -> Option<&Layer> {
+        let (name, groups) = match path.split_once('/') {
+            Some((name, groups)) => (name, Some(groups)),
+            None => (path, None),
+        };
+
+        let layer = self.layers.iter().find(|layer| layer.name == name)?;
+        match groups {
+            Some(groups) => layer.find_group(groups),
+            None => Some(layer),
+        }
+    }
+
+    /// Object addressed by its layer's path followed by its name, such as `"hud/left/meter"`.
+    /// Objects of the root layer can be addressed by their name only.
+    pub fn object(&mut self, path: &str) -> Result<&mut Object> {
+        let (layer, name) = path.rsplit_once('/').unwrap_or(("root", path));
+        self.layer(layer)?
+            .safe_object(name)
+            .ok_or(anyhow!("Object {name} does not exist in layer {layer}"))
+    }
+
+    pub fn find_object(&self, path: &str) -> Option<&Object> {
+        let (layer, name) = path.rsplit_once('/').unwrap_or(("root", path));
+        self.find_layer(layer)?.objects().get(name)
     }
 
     pub fn layer_unchecked(&mut self, name: &str) -> &mut Layer {
@@ -139,8 +189,8 @@ impl Canvas {
         self.new_layer(name)
     }
 
-    pub fn layer_exists(&self, name: &str) -> bool {
-        self.layers.iter().any(|layer| layer.name == name)
+    pub fn layer_exists(&self, path: &str) -> bool {
+        self.find_layer(path).is_some()
     }
 
     pub fn ensure_layer_exists(&self, name: &str) {
@@ -269,11 +319,21 @@ impl Canvas {
     }
 
     pub fn width(&self) -> usize {
-        self.cell_size * self.world_region.width() + 2 * self.outer_padding
+        self.world_region.size(&self.cell_size).0.round() as usize
+            + 2 * self.outer_padding
     }
 
     pub fn height(&self) -> usize {
-        self.cell_size * self.world_region.height() + 2 * self.outer_padding
+        self.world_region.size(&self.cell_size).1.round() as usize
+            + 2 * self.outer_padding
+    }
+
+    /// Top-left corner of the canvas, padding included, in pixels.
+    /// Grids that are not square can extend left of or above the origin, e.g. polar grids are centered on it.
+    pub fn origin(&self) -> (f32, f32) {
+        let ((left, top), _) = self.cell_size.bounds(&self.world_region);
+        let padding = self.outer_padding as f32;
+        (left - padding, top - padding)
     }
 
     pub fn aspect_ratio(&self) -> f32 {
@@ -286,16 +346,42 @@ impl Canvas {
             .for_each(|layer| layer.remove_all_objects_in(region));
     }
 
+    /// All objects of the canvas, including the ones nested in other objects (see `Shape::nested_objects`)
+    pub fn all_objects(&self) -> Vec<&Object> {
+        fn with_nested(object: &Object) -> Vec<&Object> {
+            std::iter::once(object)
+                .chain(
+                    object
+                        .shape
+                        .nested_objects()
+                        .into_iter()
+                        .flat_map(with_nested),
+                )
+                .collect()
+        }
+
+        self.layers
+            .iter()
+            .flat_map(|layer| layer.walk())
+            .flat_map(|layer| layer.objects().values())
+            .flat_map(with_nested)
+            .collect()
+    }
+
     /// returns a list of all unique filters used throughout the canvas
     /// used to only generate one definition per filter
     ///
     pub fn unique_filters(&self) -> Vec<Filter> {
         let mut filters: Vec<Filter> = self
-            .layers
-            .iter()
-            .flat_map(|layer| {
-                layer.objects.iter().flat_map(|(_, o)| o.filters.clone())
-            })
+            .all_objects()
+            .into_iter()
+            .flat_map(|o| o.filters.clone())
+            .chain(
+                self.layers
+                    .iter()
+                    .flat_map(|l| l.walk())
+                    .flat_map(|l| l.filters.clone()),
+            )
             .unique()
             .collect();
         filters.sort_by_key(|f| format!("{:?}", f));
@@ -304,34 +390,71 @@ impl Canvas {
 
     pub fn unique_pattern_fills(&self) -> Vec<Fill> {
         let mut fills: Vec<Fill> = self
-            .layers
-            .iter()
-            .flat_map(|layer| layer.objects.iter().flat_map(|(_, o)| o.fill))
-            .filter(|fill| matches!(fill, Fill::Hatches(..) | Fill::Dotted(..)))
+            .all_objects()
+            .into_iter()
+            .flat_map(|o| {
+                o.fill
+                    .iter()
+                    .chain(o.stroke.as_ref().map(|s| &s.fill))
+                    .cloned()
+                    .collect::<Vec<_>>()
+            })
+            .filter(|fill| fill.needs_definition())
             .unique_by(|fill| fill.pattern_id())
             .collect();
         fills.sort_by_key(|f| f.pattern_id());
         fills
     }
 
+    /// Used to only generate one definition per clip, even when objects share it
+    pub fn unique_clips(&self) -> Vec<Clip> {
+        let mut clips: Vec<Clip> = self
+            .all_objects()
+            .into_iter()
+            .filter_map(|o| o.clip_to.clone())
+            .chain(
+                self.layers
+                    .iter()
+                    .flat_map(|l| l.walk())
+                    .filter_map(|l| l.clip_to.clone()),
+            )
+            .unique_by(|clip| clip.id())
+            .collect();
+        clips.sort_by_key(|clip| clip.id());
+        clips
+    }
+
+    pub fn unique_masks(&self) -> Vec<Mask> {
+        let mut masks: Vec<Mask> = self
+            .all_objects()
+            .into_iter()
+            .filter_map(|o| o.mask.clone())
+            .unique()
+            .collect();
+        masks.sort_by_key(|mask| mask.id());
+        masks
+    }
+
     pub fn debug_region(&mut self, region: &Region, color: Color) {
         let layer = self.layer_or_empty("debug plane");
 
         layer.set(
             format!("{}_corner_sw", region).as_str(),
-            Shape::Dot(region.topleft()).colored(color),
+            Shape::Dot(region.topleft()).colored(color.clone()),
         );
         layer.set(
             format!("{}_corner_se", region).as_str(),
-            Shape::Dot(region.topright().translated(1, 0)).colored(color),
+            Shape::Dot(region.topright().translated(1, 0)).colored(color.clone()),
         );
         layer.set(
             format!("{}_corner_ne", region).as_str(),
-            Shape::Dot(region.bottomright().translated(1, 1)).colored(color),
+            Shape::Dot(region.bottomright().translated(1, 1))
+                .colored(color.clone()),
         );
         layer.set(
             format!("{}_corner_nw", region).as_str(),
-            Shape::Dot(region.bottomleft().translated(0, 1)).colored(color),
+            Shape::Dot(region.bottomleft().translated(0, 1))
+                .colored(color.clone()),
         );
         layer.set(
             format!("{}_region", region).as_str(),
@@ -341,7 +464,7 @@ impl Canvas {
     }
 
     pub fn debug_grid(&mut self, color: Color) {
-        let world = self.world_region.clone();
+        let world = self.world_region;
         let layer = self.layer_or_empty("debug_plane");
 
         let ymax = world.end.x() + 1;
@@ -354,14 +477,14 @@ impl Canvas {
             layer.set(
                 format!("grid_vertical_{x}"),
                 Shape::Line(Point::Corner(x, 0), Point::Corner(x, ymax), 1.0)
-                    .colored(color)
+                    .colored(color.clone())
                     .opacified(0.25),
             );
 
             layer.set(
                 format!("grid_horizontal_{y}"),
                 Shape::Line(Point::Corner(0, y), Point::Corner(xmax, y), 1.0)
-                    .colored(color)
+                    .colored(color.clone())
                     .opacified(0.25),
             );
         }
@@ -371,3 +494,68 @@ impl Canvas {
         (self.width().into(), self.height().into())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Point, Shape};
+
+    fn canvas_with_groups() -> Canvas {
+        let mut canvas = Canvas::new(4, 4);
+        canvas
+            .new_layer("hud")
+            .new_group("left")
+            .new_group("meter")
+            .set("needle", Shape::Dot(Point::Corner(1, 1)));
+        canvas
+    }
+
+    #[test]
+    fn groups_are_addressed_by_path() {
+        let mut canvas = canvas_with_groups();
+        assert_eq!(canvas.layer("hud/left/meter").unwrap().name, "meter");
+        assert_eq!(canvas.find_layer("hud/left").unwrap().name, "left");
+        assert!(canvas.layer("hud/right").is_err());
+        assert!(canvas.find_layer("nope/left").is_none());
+    }
+
+    #[test]
+    fn objects_are_addressed_by_path() {
+        let mut canvas = canvas_with_groups();
+        canvas.root().set("dot", Shape::Dot(Point::Corner(0, 0)));
+
+        assert!(canvas.find_object("hud/left/meter/needle").is_some());
+        assert!(canvas.object("hud/left/meter/needle").is_ok());
+        assert!(canvas.find_object("hud/left/needle").is_none());
+        // objects of the root layer don't need a path
+        assert!(canvas.find_object("dot").is_some());
+    }
+
+    #[test]
+    fn walking_through_nested_groups() {
+        let canvas = canvas_with_groups();
+        let names: Vec<_> = canvas
+            .find_layer("hud")
+            .unwrap()
+            .walk()
+            .into_iter()
+            .map(|layer| layer.name.clone())
+            .collect();
+        assert_eq!(names, vec!["hud", "left", "meter"]);
+    }
+
+    #[test]
+    fn groups_render_inside_their_parent() {
+        let mut canvas = canvas_with_groups();
+        let rendered = canvas.render_to_svg_string().unwrap();
+        let position = |needle: &str| rendered.find(needle).unwrap();
+
+        assert!(
+            position(r#"data-layer="hud""#) < position(r#"data-layer="left""#)
+        );
+        assert!(
+            position(r#"data-layer="left""#) < position(r#"data-layer="meter""#)
+        );
+        assert!(rendered.contains(r#"data-object="hud/left/meter--needle""#));
+    }
+}
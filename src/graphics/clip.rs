@@ -0,0 +1,102 @@
+use std::hash::{Hash, Hasher};
+
+use slug::slugify;
+
+use crate::{Point, Region, Shape};
+
+/// Area an object is clipped to. Anything outside of it is not drawn.
+#[derive(Debug, Clone, Hash)]
+pub enum Clip {
+    Region(Region),
+    Shape(Shape),
+    /// Union of the shapes of all objects in the layer or group at that path, as it is when rendering.
+    /// The layer can be hidden.
+    Layer(String),
+}
+
+impl Clip {
+    pub fn id(&self) -> String {
+        match self {
+            Clip::Region(region) => region.clip_path_id(),
+            Clip::Shape(shape) => {
+                let mut hasher = StableHasher::default();
+                shape.hash(&mut hasher);
+                format!("clip-shape-{:x}", hasher.finish())
+            }
+            Clip::Layer(name) => format!("clip-layer-{}", slugify(name)),
+        }
+    }
+}
+
+/// FNV-1a hasher. Unlike `DefaultHasher`, its output is the same across Rust releases, so ids don't change between renders.
+struct StableHasher(u64);
+
+impl Default for StableHasher {
+    fn default() -> Self {
+        Self(0xcbf29ce484222325)
+    }
+}
+
+impl Hasher for StableHasher {
+    fn finish(&self) -> u64 {
+        self.0
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 = (self.0 ^ *byte as u64).wrapping_mul(0x100000001b3);
+        }
+    }
+}
+
+impl From<Region> for Clip {
+    fn from(region: Region) -> Self {
+        Clip::Region(region)
+    }
+}
+
+impl From<(Point, Point)> for Clip {
+    fn from(corners: (Point, Point)) -> Self {
+        Clip::Region(corners.into())
+    }
+}
+
+impl From<Shape> for Clip {
+    fn from(shape: Shape) -> Self {
+        Clip::Shape(shape)
+    }
+}
+
+/// How a mask's content controls the visibility of the masked object
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum MaskMode {
+    /// White areas show the object, black ones hide it
+    #[default]
+    Luminance,
+    /// Opaque areas show the object, transparent ones hide it, regardless of their color
+    Alpha,
+}
+
+/// Mask made of a layer, drawn as usual. The layer can be hidden, and can be a nested group.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Mask {
+    pub layer: String,
+    pub mode: MaskMode,
+}
+
+impl Mask {
+    pub fn new(layer: impl Into<String>, mode: MaskMode) -> Self {
+        Self {
+            layer: layer.into(),
+            mode,
+        }
+    }
+
+    pub fn id(&self) -> String {
+        let mode = match self.mode {
+            MaskMode::Luminance => "luminance",
+            MaskMode::Alpha => "alpha",
+        };
+        format!("mask-{mode}-{}", slugify(&self.layer))
+    }
+}
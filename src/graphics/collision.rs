@@ -0,0 +1,426 @@
+use super::interpolation::CURVE_STEPS;
+use super::path::flatten_path;
+use super::shapes::{
+    curve_control_point, point_on_circle, quadratic_curve_point,
+};
+use crate::{Angle, BooleanOperation, Point, Region, Shape};
+
+/// Distance under which two shapes are considered touching, in cells
+const TOLERANCE: f32 = 1e-3;
+
+/// Part of the surface covered by a shape, in grid units. Curves are approximated with straight lines.
+#[derive(Debug, Clone, PartialEq)]
+enum Footprint {
+    /// Area enclosed by a closed outline
+    Area(Vec<(f32, f32)>),
+    /// Open line going through the points. A single point for dots.
+    Stroke(Vec<(f32, f32)>),
+}
+
+impl Shape {
+    /// Whether the two shapes share at least one point.
+    ///
+    /// Object sizes are not known to shapes, so dots and small circles are considered as points,
+    /// lines have no width, and text, images and components are approximated by their region.
+    ///
+    /// ```
+    /// use shapemaker::{Point, Shape};
+    /// let circle = Shape::BigCircle(Point::Corner(2, 2));
+    /// assert!(circle.intersects(&Shape::Rectangle(Point::Corner(0, 0), Point::Corner(2, 2))));
+    /// assert!(!circle.intersects(&Shape::Line(Point::Corner(0, 0), Point::Corner(0, 4), 1.0)));
+    /// ```
+    pub fn intersects(&self, other: &Shape) -> bool {
+        let (footprints, other_footprints) =
+            (self.footprints(), other.footprints());
+        let meeting = footprints.iter().any(|footprint| {
+            other_footprints.iter().any(|other| footprint.meets(other))
+        });
+
+        match (self, other) {
+            (Shape::Boolean { .. }, _) | (_, Shape::Boolean { .. }) => {
+                // the operands meeting is not enough, the point where they do has to be in the combined area
+                meeting && {
+                    let all: Vec<_> =
+                        footprints.into_iter().chain(other_footprints).collect();
+                    witnesses(&all).into_iter().any(|point| {
+                        self.contains_coords(point)
+                            && other.contains_coords(point)
+                    })
+                }
+            }
+            _ => meeting,
+        }
+    }
+
+    /// Whether the point is inside the shape, or on it for shapes without an area. See `Shape::intersects` for the approximations made.
+    pub fn contains_point(&self, point: Point) -> bool {
+        self.contains_coords(point.grid_coords())
+    }
+
+    fn contains_coords(&self, point: (f32, f32)) -> bool {
+        match self {
+            Shape::Boolean { op, a, b } => {
+                let (in_a, in_b) =
+                    (a.contains_coords(point), b.contains_coords(point));
+                match op {
+                    BooleanOperation::Union => in_a || in_b,
+                    BooleanOperation::Intersection => in_a && in_b,
+                    BooleanOperation::Difference => in_a && !in_b,
+                    BooleanOperation::Xor => in_a != in_b,
+                }
+            }
+            _ => self
+                .footprints()
+                .iter()
+                .any(|footprint| footprint.contains(point)),
+        }
+    }
+
+    fn footprints(&self) -> Vec<Footprint> {
+        let along_arc = |center: Point, radius: f32, start: Angle, end: Angle| {
+            (0..=CURVE_STEPS)
+                .map(|i| {
+                    let progress = i as f32 / CURVE_STEPS as f32;
+                    point_on_circle(
+                        center.grid_coords(),
+                        radius,
+                        Angle::from_degrees(
+                            start.degrees() + (end - start).degrees() * progress,
+                        ),
+                    )
+                })
+                .collect()
+        };
+
+        match self {
+            Shape::Path(segments) => flatten_path(segments, CURVE_STEPS)
+                .into_iter()
+                .map(|subpath| {
+                    let closed = subpath.len() > 2
+                        && distance(subpath[0], subpath[subpath.len() - 1])
+                            <= TOLERANCE;
+                    if closed {
+                        Footprint::Area(subpath)
+                    } else {
+                        Footprint::Stroke(subpath)
+                    }
+                })
+                .collect(),
+            Shape::Polygon(..)
+            | Shape::Rectangle(..)
+            | Shape::BigCircle(..)
+            | Shape::Ellipse(..)
+            | Shape::Sector { .. } => {
+                self.outline().map(Footprint::Area).into_iter().collect()
+            }
+            Shape::Line(start, end, _) => {
+                vec![Footprint::Stroke(vec![
+                    start.grid_coords(),
+                    end.grid_coords(),
+                ])]
+            }
+            Shape::CurveInward(start, end, _)
+            | Shape::CurveOutward(start, end, _) => {
+                let (start, end) = (start.grid_coords(), end.grid_coords());
+                let control = curve_control_point(
+                    start,
+                    end,
+                    matches!(self, Shape::CurveInward(..)),
+                );
+                vec![Footprint::Stroke(
+                    (0..=CURVE_STEPS)
+                        .map(|i| {
+                            quadratic_curve_point(
+                                start,
+                                control,
+                                end,
+                                i as f32 / CURVE_STEPS as f32,
+                            )
+                        })
+                        .collect(),
+                )]
+            }
+            Shape::Arc {
+                center,
+                radius_cells,
+                start,
+                end,
+            } => vec![Footprint::Stroke(along_arc(
+                *center,
+                *radius_cells,
+                *start,
+                *end,
+            ))],
+            Shape::Dot(at) | Shape::BigDot(at) => {
+                vec![Footprint::Stroke(vec![at.grid_coords()])]
+            }
+            Shape::SmallCircle(at) => {
+                vec![Footprint::Stroke(vec![at.as_centered().grid_coords()])]
+            }
+            Shape::Boolean { a, b, .. } => {
+                a.footprints().into_iter().chain(b.footprints()).collect()
+            }
+            Shape::Text(..)
+            | Shape::CenteredText(..)
+            | Shape::FittedText { .. }
+            | Shape::Image(..)
+            | Shape::RawSVG { .. }
+            | Shape::Tiling(..)
+            | Shape::Component { .. } => {
+                vec![Footprint::Area(region_outline(&self.region()))]
+            }
+        }
+    }
+}
+
+impl Footprint {
+    fn points(&self) -> &[(f32, f32)] {
+        match self {
+            Footprint::Area(points) | Footprint::Stroke(points) => points,
+        }
+    }
+
+    /// Segments making up the footprint, including the closing one for areas. A single point is a segment of length zero.
+    fn edges(&self) -> Vec<((f32, f32), (f32, f32))> {
+        let points = self.points();
+        match self {
+            Footprint::Stroke(points) if points.len() == 1 => {
+                vec![(points[0], points[0])]
+            }
+            Footprint::Stroke(_) => {
+                points.windows(2).map(|pair| (pair[0], pair[1])).collect()
+            }
+            Footprint::Area(_) => points
+                .iter()
+                .zip(points.iter().cycle().skip(1))
+                .map(|(from, to)| (*from, *to))
+                .collect(),
+        }
+    }
+
+    fn bounding_box(&self) -> ((f32, f32), (f32, f32)) {
+        self.points().iter().fold(
+            (
+                (f32::INFINITY, f32::INFINITY),
+                (f32::NEG_INFINITY, f32::NEG_INFINITY),
+            ),
+            |((min_x, min_y), (max_x, max_y)), &(x, y)| {
+                ((min_x.min(x), min_y.min(y)), (max_x.max(x), max_y.max(y)))
+            },
+        )
+    }
+
+    fn contains(&self, point: (f32, f32)) -> bool {
+        let on_edge = self
+            .edges()
+            .iter()
+            .any(|&(from, to)| distance_to_segment(point, from, to) <= TOLERANCE);
+
+        match self {
+            Footprint::Stroke(_) => on_edge,
+            // even-odd rule, like SVG's fill-rule
+            Footprint::Area(_) => {
+                on_edge
+                    || self
+                        .edges()
+                        .iter()
+                        .filter(|&&((x1, y1), (x2, y2))| {
+                            (y1 > point.1) != (y2 > point.1)
+                                && point.0
+                                    < x1 + (point.1 - y1) * (x2 - x1) / (y2 - y1)
+                        })
+                        .count()
+                        % 2
+                        == 1
+            }
+        }
+    }
+
+    fn meets(&self, other: &Footprint) -> bool {
+        let ((min_x, min_y), (max_x, max_y)) = self.bounding_box();
+        let ((other_min_x, other_min_y), (other_max_x, other_max_y)) =
+            other.bounding_box();
+        if min_x > other_max_x + TOLERANCE
+            || other_min_x > max_x + TOLERANCE
+            || min_y > other_max_y + TOLERANCE
+            || other_min_y > max_y + TOLERANCE
+        {
+            return false;
+        }
+
+        let edges = other.edges();
+        let crossing = self.edges().iter().any(|&(from, to)| {
+            edges.iter().any(|&(other_from, other_to)| {
+                segments_meet(from, to, other_from, other_to)
+            })
+        });
+
+        // without crossing edges, a footprint can still be entirely inside an area
+        crossing
+            || matches!(other, Footprint::Area(_))
+                && other.contains(self.points()[0])
+            || matches!(self, Footprint::Area(_))
+                && self.contains(other.points()[0])
+    }
+}
+
+/// Points where shapes made of these footprints can meet: their vertices, and where their edges cross.
+fn witnesses(footprints: &[Footprint]) -> Vec<(f32, f32)> {
+    let edges: Vec<_> = footprints.iter().flat_map(Footprint::edges).collect();
+    let crossings = edges.iter().enumerate().flat_map(|(i, &edge)| {
+        edges[i + 1..]
+            .iter()
+            .filter_map(move |&other| crossing_point(edge, other))
+    });
+
+    footprints
+        .iter()
+        .flat_map(|footprint| footprint.points().iter().copied())
+        .chain(crossings)
+        .collect()
+}
+
+/// Where the two segments cross, if they do and aren't parallel
+fn crossing_point(
+    (from, to): ((f32, f32), (f32, f32)),
+    (other_from, other_to): ((f32, f32), (f32, f32)),
+) -> Option<(f32, f32)> {
+    let (dx, dy) = (to.0 - from.0, to.1 - from.1);
+    let (other_dx, other_dy) =
+        (other_to.0 - other_from.0, other_to.1 - other_from.1);
+    let denominator = dx * other_dy - dy * other_dx;
+    if denominator.abs() <= f32::EPSILON {
+        return None;
+    }
+
+    let (ox, oy) = (other_from.0 - from.0, other_from.1 - from.1);
+    let t = (ox * other_dy - oy * other_dx) / denominator;
+    let u = (ox * dy - oy * dx) / denominator;
+    ((0.0..=1.0).contains(&t) && (0.0..=1.0).contains(&u))
+        .then_some((from.0 + t * dx, from.1 + t * dy))
+}
+
+/// Outline of the cells covered by the region
+fn region_outline(region: &Region) -> Vec<(f32, f32)> {
+    let ((x, y), _) = region.cells();
+    let (x, y) = (x as f32, y as f32);
+    let (w, h) = region.size(1);
+    vec![(x, y), (x + w, y), (x + w, y + h), (x, y + h)]
+}
+
+fn distance((x1, y1): (f32, f32), (x2, y2): (f32, f32)) -> f32 {
+    ((x2 - x1).powi(2) + (y2 - y1).powi(2)).sqrt()
+}
+
+fn distance_to_segment(
+    point: (f32, f32),
+    from: (f32, f32),
+    to: (f32, f32),
+) -> f32 {
+    let (dx, dy) = (to.0 - from.0, to.1 - from.1);
+    let length_squared = dx * dx + dy * dy;
+    if length_squared == 0.0 {
+        return distance(point, from);
+    }
+
+    let t = (((point.0 - from.0) * dx + (point.1 - from.1) * dy)
+        / length_squared)
+        .clamp(0.0, 1.0);
+    distance(point, (from.0 + t * dx, from.1 + t * dy))
+}
+
+fn segments_meet(
+    from: (f32, f32),
+    to: (f32, f32),
+    other_from: (f32, f32),
+    other_to: (f32, f32),
+) -> bool {
+    // which side of the segment the point is on
+    let side =
+        |(x1, y1): (f32, f32), (x2, y2): (f32, f32), (x, y): (f32, f32)| {
+            (x2 - x1) * (y - y1) - (y2 - y1) * (x - x1)
+        };
+
+    let crossing = side(from, to, other_from) * side(from, to, other_to) < 0.0
+        && side(other_from, other_to, from) * side(other_from, other_to, to)
+            < 0.0;
+
+    crossing
+        || distance_to_segment(from, other_from, other_to) <= TOLERANCE
+        || distance_to_segment(to, other_from, other_to) <= TOLERANCE
+        || distance_to_segment(other_from, from, to) <= TOLERANCE
+        || distance_to_segment(other_to, from, to) <= TOLERANCE
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn square(x: usize, y: usize, size: usize) -> Shape {
+        Shape::Rectangle(Point::Corner(x, y), Point::Corner(x + size, y + size))
+    }
+
+    fn dot(x: f32, y: f32) -> Shape {
+        Shape::Dot(Point::at(x, y))
+    }
+
+    #[test]
+    fn regions_off_the_grid() {
+        let image = Shape::Image(
+            Region::covering((-3.0, -3.0), (-1.0, -1.0)),
+            "image.png".into(),
+        );
+        assert!(image.intersects(&dot(-2.0, -2.0)));
+        assert!(!image.intersects(&dot(1.0, 1.0)));
+    }
+
+    #[test]
+    fn differences_intersect_their_first_operand_outside_the_second() {
+        let difference = square(0, 0, 4).difference(square(1, 1, 2));
+        assert!(difference.intersects(&dot(0.5, 0.5)));
+        assert!(!difference.intersects(&dot(2.0, 2.0)));
+        assert!(!difference.intersects(&dot(6.0, 6.0)));
+        // crosses the hole and the ring around it
+        let line = Shape::Line(Point::Corner(2, 2), Point::Corner(2, 6), 1.0);
+        assert!(difference.intersects(&line));
+        assert!(!difference.intersects(&square(2, 2, 0)));
+    }
+
+    #[test]
+    fn intersections_intersect_where_both_operands_do() {
+        let apart = square(0, 0, 2).intersection(square(4, 0, 2));
+        let line = Shape::Line(Point::Corner(0, 1), Point::Corner(6, 1), 1.0);
+        // the line touches both operands, but they don't overlap
+        assert!(!apart.intersects(&line));
+        assert!(!apart.intersects(&dot(1.0, 1.0)));
+
+        let overlapping = square(0, 0, 2).intersection(square(1, 0, 2));
+        assert!(overlapping.intersects(&line));
+        assert!(overlapping.intersects(&dot(1.5, 1.0)));
+        assert!(!overlapping.intersects(&dot(0.5, 1.0)));
+    }
+
+    #[test]
+    fn unions_and_xors_intersect_what_either_operand_does() {
+        for op in [BooleanOperation::Union, BooleanOperation::Xor] {
+            let combined = square(0, 0, 2).combined(op, square(1, 1, 2));
+            assert!(combined.intersects(&dot(0.5, 0.5)));
+            assert!(combined.intersects(&dot(3.5, 3.5)));
+            assert!(!combined.intersects(&dot(5.0, 5.0)));
+        }
+
+        let union = square(0, 0, 2).union(square(1, 1, 2));
+        let xor = square(0, 0, 2).xor(square(1, 1, 2));
+        assert!(union.intersects(&dot(1.5, 1.5)));
+        assert!(!xor.intersects(&dot(1.5, 1.5)));
+    }
+
+    #[test]
+    fn booleans_on_either_side() {
+        let difference = square(0, 0, 4).difference(square(1, 1, 2));
+        assert_eq!(
+            difference.intersects(&square(3, 3, 2)),
+            square(3, 3, 2).intersects(&difference)
+        );
+    }
+}
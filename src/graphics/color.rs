@@ -1,19 +1,21 @@
 use std::{
-    collections::HashMap,
-    fs::File,
-    io::{BufRead, BufReader},
+    collections::{BTreeMap, HashMap},
     path::PathBuf,
+    sync::Arc,
 };
 
+use anyhow::{Result, anyhow};
 use serde::Deserialize;
-use strum::IntoEnumIterator;
-use strum_macros::EnumIter;
 #[cfg(feature = "web")]
 use wasm_bindgen::prelude::*;
 
-#[cfg_attr(feature = "web", wasm_bindgen)]
-#[derive(Debug, Clone, Copy, PartialEq, EnumIter)]
+use crate::{Rgba, graphics::themes::without_jsonc};
+
+/// Color of the palette. The 12 named colors are always available, and palettes can define any number of other swatches, see `Color::named`.
+/// Colors can also be exact, whatever the palette, such as the ones made by `Color::darken` or `Color::mix`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
 pub enum Color {
+    #[default]
     Black,
     White,
     Red,
@@ -26,58 +28,140 @@ pub enum Color {
     Cyan,
     Pink,
     Gray,
+    /// Any other swatch of the palette
+    Named(Arc<str>),
+    /// Color that does not depend on the palette
+    Exact(Rgba),
 }
 
 impl Color {
+    /// The 12 colors every palette has
     pub fn all() -> Vec<Color> {
-        Color::iter().collect()
+        vec![
+            Color::Black,
+            Color::White,
+            Color::Red,
+            Color::Green,
+            Color::Blue,
+            Color::Yellow,
+            Color::Orange,
+            Color::Purple,
+            Color::Brown,
+            Color::Cyan,
+            Color::Pink,
+            Color::Gray,
+        ]
     }
-}
 
-impl Default for Color {
-    fn default() -> Self {
-        Self::Black
+    /// Swatch of the palette with that name. Gives one of the 12 default colors when the name is theirs,
+    /// and an exact color for hexadecimal notations such as `#ff8000`.
+    pub fn named(name: &str) -> Self {
+        if name.starts_with('#')
+            && let Ok(rgba) = name.parse()
+        {
+            return Color::Exact(rgba);
+        }
+
+        Color::all()
+            .into_iter()
+            .find(|color| color.name() == name)
+            .unwrap_or_else(|| Color::Named(name.into()))
     }
 }
 
 impl From<&str> for Color {
     fn from(s: &str) -> Self {
-        match s {
-            "black" => Color::Black,
-            "white" => Color::White,
-            "red" => Color::Red,
-            "green" => Color::Green,
-            "blue" => Color::Blue,
-            "yellow" => Color::Yellow,
-            "orange" => Color::Orange,
-            "purple" => Color::Purple,
-            "brown" => Color::Brown,
-            "cyan" => Color::Cyan,
-            "pink" => Color::Pink,
-            "gray" => Color::Gray,
-            _ => panic!("Invalid color: {}", s),
-        }
+        Color::named(s)
     }
 }
 
 impl Color {
-    pub fn render(self, mapping: &ColorMapping) -> String {
+    /// Value of the color in the palette. Swatches missing from the palette are rendered as their name, which works for CSS color names.
+    pub fn render(&self, mapping: &ColorMapping) -> String {
+        if let Color::Exact(rgba) = self {
+            return rgba.to_string();
+        }
+
+        let name = self.name();
+        mapping.get(&name).unwrap_or(name)
+    }
+
+    /// Fails for swatches that are neither in the palette nor CSS color names, since they would render as invalid CSS
+    pub fn ensure_renderable(&self, mapping: &ColorMapping) -> Result<()> {
         match self {
-            Color::Black => mapping.black.to_string(),
-            Color::White => mapping.white.to_string(),
-            Color::Red => mapping.red.to_string(),
-            Color::Green => mapping.green.to_string(),
-            Color::Blue => mapping.blue.to_string(),
-            Color::Yellow => mapping.yellow.to_string(),
-            Color::Orange => mapping.orange.to_string(),
-            Color::Purple => mapping.purple.to_string(),
-            Color::Brown => mapping.brown.to_string(),
-            Color::Cyan => mapping.cyan.to_string(),
-            Color::Pink => mapping.pink.to_string(),
-            Color::Gray => mapping.gray.to_string(),
+            Color::Named(name)
+                if mapping.get(name).is_none()
+                    && name.parse::<Rgba>().is_err() =>
+            {
+                Err(anyhow!(
+                    "Color {name:?} is not in the palette, and is not a CSS color"
+                ))
+            }
+            _ => Ok(()),
         }
     }
 
+    /// Actual color, using the palette's value for its swatches.
+    /// Fails when that value is not a CSS color, such as a gradient.
+    pub fn resolve(&self, mapping: &ColorMapping) -> Result<Rgba> {
+        if let Color::Exact(rgba) = self {
+            return Ok(*rgba);
+        }
+
+        self.render(mapping).parse().map_err(|e| {
+            anyhow!("Can't resolve color {} of the palette: {e}", self.name())
+        })
+    }
+
+    /// Lighter color, see `Rgba::lighten`
+    ///
+    /// ```
+    /// use shapemaker::{Color, ColorMapping};
+    ///
+    /// let palette = ColorMapping::from([("red", "#ff0000")]);
+    /// let lighter = Color::Red.lighten(0.3, &palette).unwrap();
+    /// assert_eq!(lighter.render(&palette), "#ff7f6e");
+    /// // the result is an exact color, that stays the same whatever the palette
+    /// assert_eq!(lighter.render(&palette.with("red", "#0000ff")), "#ff7f6e");
+    /// ```
+    pub fn lighten(&self, amount: f32, mapping: &ColorMapping) -> Result<Color> {
+        Ok(Color::Exact(self.resolve(mapping)?.lighten(amount)))
+    }
+
+    /// Darker color, see `Rgba::darken`
+    ///
+    /// ```
+    /// use shapemaker::{Color, ColorMapping};
+    ///
+    /// let palette = ColorMapping::from([("red", "#ff0000")]);
+    /// let darker = Color::Red.darken(0.3, &palette).unwrap();
+    /// assert_eq!(darker.render(&palette), "#9e0000");
+    /// ```
+    pub fn darken(&self, amount: f32, mapping: &ColorMapping) -> Result<Color> {
+        Ok(Color::Exact(self.resolve(mapping)?.darken(amount)))
+    }
+
+    /// Color `ratio` of the way to `other`, see `Rgba::mix`
+    pub fn mix(
+        &self,
+        other: Color,
+        ratio: f32,
+        mapping: &ColorMapping,
+    ) -> Result<Color> {
+        Ok(Color::Exact(
+            self.resolve(mapping)?.mix(other.resolve(mapping)?, ratio),
+        ))
+    }
+
+    /// Same color, with the given opacity, from 0 to 1
+    pub fn with_opacity(
+        &self,
+        opacity: f32,
+        mapping: &ColorMapping,
+    ) -> Result<Color> {
+        Ok(Color::Exact(self.resolve(mapping)?.with_opacity(opacity)))
+    }
+
     pub fn name(&self) -> String {
         match self {
             Color::Black => "black",
@@ -92,26 +176,31 @@ impl Color {
             Color::Cyan => "cyan",
             Color::Pink => "pink",
             Color::Gray => "gray",
+            Color::Named(name) => return name.to_string(),
+            Color::Exact(rgba) => return rgba.to_string(),
         }
         .to_string()
     }
 }
 
-#[cfg_attr(feature = "web", wasm_bindgen(getter_with_clone))]
-#[derive(Debug, Deserialize, Clone)]
+/// Values of the palette's swatches, by name. Values can be any CSS color.
+///
+/// Palettes loaded from files or strings are validated, and their values normalized to hexadecimal notations:
+///
+/// ```
+/// use shapemaker::ColorMapping;
+///
+/// let palette = ColorMapping::from_css(":root { --accent: rgb(255 128 0); }").unwrap();
+/// assert_eq!(palette.get("accent").unwrap(), "#ff8000");
+///
+/// let error = ColorMapping::from_json(r#"{"red": "banana"}"#).unwrap_err();
+/// assert!(error.to_string().contains("red"));
+/// ```
+#[cfg_attr(feature = "web", wasm_bindgen)]
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+#[serde(try_from = "HashMap<String, String>")]
 pub struct ColorMapping {
-    pub black: String,
-    pub white: String,
-    pub red: String,
-    pub green: String,
-    pub blue: String,
-    pub yellow: String,
-    pub orange: String,
-    pub purple: String,
-    pub brown: String,
-    pub cyan: String,
-    pub pink: String,
-    pub gray: String,
+    swatches: BTreeMap<String, String>,
 }
 
 #[cfg_attr(feature = "web", wasm_bindgen)]
@@ -120,33 +209,25 @@ impl ColorMapping {
     #[allow(clippy::should_implement_trait)]
     pub fn default() -> Self {
         ColorMapping {
-            black: "black".to_string(),
-            white: "white".to_string(),
-            red: "red".to_string(),
-            green: "green".to_string(),
-            blue: "blue".to_string(),
-            yellow: "yellow".to_string(),
-            orange: "orange".to_string(),
-            purple: "purple".to_string(),
-            brown: "brown".to_string(),
-            pink: "pink".to_string(),
-            gray: "gray".to_string(),
-            cyan: "cyan".to_string(),
+            swatches: Color::all()
+                .into_iter()
+                .map(|color| (color.name(), color.name()))
+                .collect(),
         }
     }
 
-    pub fn from_json(content: &str) -> ColorMapping {
-        let json: HashMap<String, String> =
-            serde_json::from_str(content).unwrap();
-        ColorMapping::from_hashmap(json)
+    pub fn get(&self, name: &str) -> Option<String> {
+        self.swatches.get(name).cloned()
     }
 
-    pub fn from_css(content: &str) -> ColorMapping {
-        let mut mapping = ColorMapping::default();
-        for line in content.lines() {
-            mapping.from_css_line(line);
-        }
-        mapping
+    /// Adds a swatch to the palette, or changes its value
+    pub fn set(&mut self, name: &str, value: &str) {
+        self.swatches.insert(name.to_string(), value.to_string());
+    }
+
+    /// Names of all the swatches of the palette, in alphabetical order
+    pub fn names(&self) -> Vec<String> {
+        self.swatches.keys().cloned().collect()
     }
 }
 
@@ -157,123 +238,317 @@ impl Default for ColorMapping {
     }
 }
 
+impl<K: Into<String>, V: Into<String>> FromIterator<(K, V)> for ColorMapping {
+    /// Swatches not given keep their default value
+    fn from_iter<T: IntoIterator<Item = (K, V)>>(iter: T) -> Self {
+        let mut mapping = ColorMapping::default();
+        mapping.swatches.extend(
+            iter.into_iter()
+                .map(|(name, value)| (name.into(), value.into())),
+        );
+        mapping
+    }
+}
+
+impl<K: Into<String>, V: Into<String>, const N: usize> From<[(K, V); N]>
+    for ColorMapping
+{
+    fn from(swatches: [(K, V); N]) -> Self {
+        swatches.into_iter().collect()
+    }
+}
+
+impl TryFrom<HashMap<String, String>> for ColorMapping {
+    type Error = anyhow::Error;
+
+    fn try_from(hashmap: HashMap<String, String>) -> Result<Self> {
+        ColorMapping::from_hashmap(hashmap)
+    }
+}
+
 impl ColorMapping {
-    pub fn from_cli_args(args: &Vec<String>) -> ColorMapping {
-        let mut colormap: HashMap<String, String> = HashMap::new();
-        for mapping in args {
-            if !mapping.contains(':') {
-                println!("Invalid color mapping: {}", mapping);
-                std::process::exit(1);
-            }
-            let mut split = mapping.split(':');
-            let color = split.next().unwrap();
-            let hex = split.next().unwrap();
-            colormap.insert(color.to_string(), hex.to_string());
+    /// Palette of `name:value` arguments
+    pub fn from_cli_args(args: &[String]) -> Result<ColorMapping> {
+        let mut mapping = ColorMapping::default();
+        for arg in args {
+            let Some((name, value)) = arg.split_once(':') else {
+                return Err(anyhow!(
+                    "Invalid color mapping {arg:?}, expected name:value"
+                ));
+            };
+            mapping.set_validated(name, value)?;
         }
-        ColorMapping::from_hashmap(colormap)
+        Ok(mapping)
     }
 
-    pub fn from_hashmap(hashmap: HashMap<String, String>) -> ColorMapping {
-        ColorMapping {
-            black: hashmap
-                .get("black")
-                .unwrap_or(&ColorMapping::default().black)
-                .to_string(),
-            white: hashmap
-                .get("white")
-                .unwrap_or(&ColorMapping::default().white)
-                .to_string(),
-            red: hashmap
-                .get("red")
-                .unwrap_or(&ColorMapping::default().red)
-                .to_string(),
-            green: hashmap
-                .get("green")
-                .unwrap_or(&ColorMapping::default().green)
-                .to_string(),
-            blue: hashmap
-                .get("blue")
-                .unwrap_or(&ColorMapping::default().blue)
-                .to_string(),
-            yellow: hashmap
-                .get("yellow")
-                .unwrap_or(&ColorMapping::default().yellow)
-                .to_string(),
-            orange: hashmap
-                .get("orange")
-                .unwrap_or(&ColorMapping::default().orange)
-                .to_string(),
-            purple: hashmap
-                .get("purple")
-                .unwrap_or(&ColorMapping::default().purple)
-                .to_string(),
-            brown: hashmap
-                .get("brown")
-                .unwrap_or(&ColorMapping::default().brown)
-                .to_string(),
-            cyan: hashmap
-                .get("cyan")
-                .unwrap_or(&ColorMapping::default().cyan)
-                .to_string(),
-            pink: hashmap
-                .get("pink")
-                .unwrap_or(&ColorMapping::default().pink)
-                .to_string(),
-            gray: hashmap
-                .get("gray")
-                .unwrap_or(&ColorMapping::default().gray)
-                .to_string(),
+    pub fn from_hashmap(
+        hashmap: HashMap<String, String>,
+    ) -> Result<ColorMapping> {
+        let mut mapping = ColorMapping::default();
+        for (name, value) in hashmap {
+            mapping.set_validated(&name, &value)?;
         }
+        Ok(mapping)
+    }
+
+    pub fn with(mut self, name: &str, value: &str) -> Self {
+        self.set(name, value);
+        self
     }
 
-    pub fn from_file(path: PathBuf) -> ColorMapping {
-        match path.extension().map(|e| e.to_str().unwrap()) {
+    /// All the swatches of the palette, including the 12 default colors
+    pub fn colors(&self) -> Vec<Color> {
+        self.swatches
+            .keys()
+            .map(|name| Color::named(name))
+            .collect()
+    }
+
+    /// Loads a palette file, depending on its extension:
+    /// CSS, JSON (see `ColorMapping::from_json`), or base16 YAML (`.yaml` or `.yml`).
+    pub fn from_file(path: PathBuf) -> Result<ColorMapping> {
+        match path.extension().and_then(|e| e.to_str()) {
             Some("css") => ColorMapping::from_css_file(path),
             Some("json") => ColorMapping::from_json_file(path),
-            ext => panic!(
-                "Invalid colormap file format. Must be css or json, is {:?}.",
+            Some("yaml" | "yml") => {
+                ColorMapping::from_base16_yaml(&read_palette(&path)?)
+            }
+            ext => Err(anyhow!(
+                "Invalid colormap file format. Must be css, json or yaml, is {:?}.",
                 ext
-            ),
+            )),
+        }
+    }
+
+    /// Palette from a JSON object of swatch names to values, comments and trailing commas allowed, with an optional `name` for the palette itself, such as Windows Terminal color schemes.
+    /// VS Code and TextMate (tmTheme) themes are recognized too, see `ColorMapping::from_vscode_theme` and `ColorMapping::from_tmtheme`.
+    pub fn from_json(content: &str) -> Result<ColorMapping> {
+        let json: serde_json::Value =
+            serde_json::from_str(&without_jsonc(content))
+                .map_err(|e| anyhow!("Invalid color mapping JSON: {e}"))?;
+
+        if json.get("colors").is_some_and(|c| c.is_object())
+            || json.get("tokenColors").is_some_and(|t| t.is_array())
+        {
+            return ColorMapping::from_vscode_json(&json);
+        }
+        if json.get("settings").is_some_and(|s| s.is_array()) {
+            return ColorMapping::from_tmtheme_json(&json);
+        }
+
+        let Some(swatches) = json.as_object() else {
+            return Err(anyhow!("A color mapping must be a JSON object"));
+        };
+
+        let mut mapping = ColorMapping::default();
+        for (name, value) in swatches {
+            match value.as_str() {
+                // the name of the palette itself
+                _ if name == "name" => {}
+                Some(value) => mapping.set_validated(name, value)?,
+                None => {
+                    return Err(anyhow!(
+                        "Invalid color for {name:?}: {value} is not a string"
+                    ));
+                }
+            }
         }
+        Ok(mapping)
     }
 
-    pub fn from_json_file(path: PathBuf) -> ColorMapping {
-        let file = File::open(path).unwrap();
-        let reader = BufReader::new(file);
-        let json: HashMap<String, String> =
-            serde_json::from_reader(reader).unwrap();
-        ColorMapping::from_hashmap(json)
+    pub fn from_json_file(path: PathBuf) -> Result<ColorMapping> {
+        ColorMapping::from_json(&read_palette(&path)?)
     }
 
-    pub fn from_css_file(path: PathBuf) -> ColorMapping {
+    /// Palette from CSS declarations, such as `red: #cf0a2b;`, in any rule. Custom properties (`--red: #cf0a2b;`) are named without their dashes.
+    /// Custom properties and the 12 default colors must be valid colors. Other declarations are only swatches when their value is a color,
+    /// so that palettes can live in a stylesheet with properties such as `font-family` or `margin`.
+    pub fn from_css(content: &str) -> Result<ColorMapping> {
+        // comments could contain colons too
+        let mut content = content.to_string();
+        while let Some(start) = content.find("/*") {
+            let end = content[start..]
+                .find("*/")
+                .map_or(content.len(), |end| start + end + 2);
+            content.replace_range(start..end, "");
+        }
+
         let mut mapping = ColorMapping::default();
-        let file = File::open(path).unwrap();
-        let lines = std::io::BufReader::new(file).lines();
-        for line in lines.map_while(Result::ok) {
-            mapping.from_css_line(&line);
+        for declaration in content.split([';', '\n']) {
+            mapping.from_css_declaration(declaration)?;
         }
-        mapping
+        Ok(mapping)
+    }
+
+    pub fn from_css_file(path: PathBuf) -> Result<ColorMapping> {
+        ColorMapping::from_css(&read_palette(&path)?)
     }
 
     #[allow(clippy::wrong_self_convention)]
-    fn from_css_line(&mut self, line: &str) {
-        if let Some((name, value)) = line.trim().split_once(':') {
-            let value = value.trim().trim_end_matches(';').to_owned();
-            match name.trim() {
-                "black" => self.black = value,
-                "white" => self.white = value,
-                "red" => self.red = value,
-                "green" => self.green = value,
-                "blue" => self.blue = value,
-                "yellow" => self.yellow = value,
-                "orange" => self.orange = value,
-                "purple" => self.purple = value,
-                "brown" => self.brown = value,
-                "cyan" => self.cyan = value,
-                "pink" => self.pink = value,
-                "gray" => self.gray = value,
-                _ => (),
-            }
+    fn from_css_declaration(&mut self, declaration: &str) -> Result<()> {
+        // skips selectors such as `:root {`
+        let declaration = declaration
+            .rsplit('{')
+            .next()
+            .unwrap_or_default()
+            .replace('}', "");
+
+        let Some((property, value)) = declaration.split_once(':') else {
+            return Ok(());
+        };
+        let (property, value) = (property.trim(), value.trim());
+        let name = property.trim_start_matches("--");
+        if name.is_empty() {
+            return Ok(());
+        }
+
+        // other properties of the stylesheet, such as `font-family`, are not swatches
+        let is_swatch = property.starts_with("--")
+            || Color::all().iter().any(|color| color.name() == name);
+        if is_swatch {
+            self.set_validated(name, value)?;
+        } else if let Ok(rgba) = value.parse::<Rgba>() {
+            self.set(name, &rgba.to_string());
         }
+        Ok(())
+    }
+
+    /// Sets the swatch to the value, if it is a valid CSS color, normalized to its hexadecimal notation
+    pub(crate) fn set_validated(
+        &mut self,
+        name: &str,
+        value: &str,
+    ) -> Result<()> {
+        let rgba: Rgba = value
+            .parse()
+            .map_err(|e| anyhow!("Invalid color for {name:?}: {e}"))?;
+        self.set(name, &rgba.to_string());
+        Ok(())
+    }
+}
+
+fn read_palette(path: &PathBuf) -> Result<String> {
+    std::fs::read_to_string(path)
+        .map_err(|e| anyhow!("Can't read color mapping {}: {e}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn colors_by_name() {
+        assert_eq!(Color::named("red"), Color::Red);
+        assert_eq!(Color::from("gray"), Color::Gray);
+        assert_eq!(Color::named("accent"), Color::Named("accent".into()));
+        assert!(matches!(Color::named("#ff8000"), Color::Exact(_)));
+        assert_eq!(Color::named("accent").name(), "accent");
+    }
+
+    #[test]
+    fn palettes_hold_any_swatch() {
+        let palette =
+            ColorMapping::from([("red", "#ff0000"), ("accent", "#ff8000")]);
+        assert_eq!(Color::Red.render(&palette), "#ff0000");
+        assert_eq!(Color::named("accent").render(&palette), "#ff8000");
+        // defaults stay, and missing swatches render as their name
+        assert_eq!(Color::Blue.render(&palette), "blue");
+        assert_eq!(Color::named("teal").render(&palette), "teal");
+        assert!(Color::named("teal").ensure_renderable(&palette).is_ok());
+        assert!(Color::named("accent").ensure_renderable(&palette).is_ok());
+        let error = Color::named("gren")
+            .ensure_renderable(&palette)
+            .unwrap_err();
+        assert!(error.to_string().contains("gren"));
+
+        let colors = palette.colors();
+        assert_eq!(colors.len(), 13);
+        assert!(colors.contains(&Color::named("accent")));
+        assert!(colors.contains(&Color::Pink));
+    }
+
+    #[test]
+    fn operations_resolve_the_palette() {
+        let palette =
+            ColorMapping::from([("red", "#ff0000"), ("blue", "#0000ff")]);
+        assert_eq!(Color::Red.resolve(&palette).unwrap().to_string(), "#ff0000");
+        assert_eq!(
+            Color::Red.mix(Color::Blue, 0.5, &palette).unwrap().name(),
+            "#8c53a2"
+        );
+        assert_eq!(
+            Color::Red
+                .with_opacity(0.5, &palette)
+                .unwrap()
+                .render(&palette),
+            "#ff000080"
+        );
+
+        let gradient = palette.with("red", "linear-gradient(red, blue)");
+        let error = Color::Red.lighten(0.5, &gradient).unwrap_err();
+        assert!(error.to_string().contains("red"));
+    }
+
+    #[test]
+    fn palettes_in_mixed_stylesheets() {
+        let palette = ColorMapping::from_css(
+            "/* colors: the palette */
+            body {
+                font-family: Inter, sans-serif;
+                margin: 0 auto;
+                red: #cf0a2b;
+                border: rgb(0 0 255);
+            }
+            :root { --accent: hsl(30, 100%, 50%); }",
+        )
+        .unwrap();
+
+        assert_eq!(palette.get("red").unwrap(), "#cf0a2b");
+        assert_eq!(palette.get("accent").unwrap(), "#ff8000");
+        assert_eq!(palette.get("border").unwrap(), "#0000ff");
+        assert_eq!(palette.get("font-family"), None);
+        assert_eq!(palette.get("margin"), None);
+    }
+
+    #[test]
+    fn invalid_css_swatches_are_reported() {
+        let error = ColorMapping::from_css(":root { red: banana; }").unwrap_err();
+        assert!(error.to_string().contains("\"red\""));
+
+        let error = ColorMapping::from_css(":root { --gap: 4px; }").unwrap_err();
+        assert!(error.to_string().contains("\"gap\""));
+    }
+
+    #[test]
+    fn palettes_from_json_and_cli_arguments() {
+        let palette =
+            ColorMapping::from_json(r#"{"name": "Mine", "red": "rgb(255 0 0)"}"#)
+                .unwrap();
+        assert_eq!(palette.get("red").unwrap(), "#ff0000");
+        assert_eq!(palette.get("name"), None);
+        assert!(ColorMapping::from_json(r#"{"red": 3}"#).is_err());
+        assert!(ColorMapping::from_json("[]").is_err());
+
+        let palette =
+            ColorMapping::from_cli_args(&["accent:#ff8000".to_string()]).unwrap();
+        assert_eq!(palette.get("accent").unwrap(), "#ff8000");
+        assert!(ColorMapping::from_cli_args(&["accent".to_string()]).is_err());
+    }
+
+    #[test]
+    fn palettes_from_files() {
+        let colorschemes = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("examples/colorschemes");
+        let css = ColorMapping::from_file(colorschemes.join("palenight.css"));
+        assert_eq!(css.unwrap().get("red").unwrap(), "#cf0a2b");
+        assert!(
+            ColorMapping::from_file(colorschemes.join("palenight.json")).is_ok()
+        );
+        assert!(
+            ColorMapping::from_file(colorschemes.join("palenight.txt")).is_err()
+        );
+        assert!(
+            ColorMapping::from_file(colorschemes.join("missing.css")).is_err()
+        );
     }
 }
@@ -1,6 +1,6 @@
-use crate::{Angle, Color, ColorMapping, rendering::svg};
+use crate::{Angle, Color, ColorMapping, geometry::hash_floats, rendering::svg};
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone)]
 pub enum Fill {
     Solid(Color),
     Translucent(Color, f32),
@@ -8,6 +8,74 @@ pub enum Fill {
     Hatches(Color, Angle, f32, f32),
     /// Dotted(color, diameter, spacing)
     Dotted(Color, f32, f32),
+    /// LinearGradient(stops, angle). At 0°, the gradient goes from left to right, at 90° from top to bottom.
+    /// Gradients stretch over the object's bounding box, so perfectly horizontal or vertical lines can't be painted with one.
+    LinearGradient(Vec<GradientStop>, Angle),
+    /// RadialGradient(stops, focal_point). The gradient goes from the focal point to the edges of the object's bounding box.
+    /// The focal point is relative to the bounding box: (0.5, 0.5) is its center.
+    RadialGradient(Vec<GradientStop>, (f32, f32)),
+}
+
+/// Color of a gradient at some point along it
+#[derive(Debug, Clone, PartialEq)]
+pub struct GradientStop {
+    /// From 0 (start of the gradient) to 1 (end of the gradient)
+    pub offset: f32,
+    pub color: Color,
+    pub opacity: f32,
+}
+
+impl From<(f32, Color)> for GradientStop {
+    fn from((offset, color): (f32, Color)) -> Self {
+        Self {
+            offset,
+            color,
+            opacity: 1.0,
+        }
+    }
+}
+
+impl From<(f32, Color, f32)> for GradientStop {
+    fn from((offset, color, opacity): (f32, Color, f32)) -> Self {
+        Self {
+            offset,
+            color,
+            opacity,
+        }
+    }
+}
+
+impl std::hash::Hash for GradientStop {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.color.hash(state);
+        hash_floats(&[self.offset, self.opacity], state);
+    }
+}
+
+impl std::hash::Hash for Fill {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        std::mem::discriminant(self).hash(state);
+        match self {
+            Fill::Solid(color) => color.hash(state),
+            Fill::Translucent(color, opacity) => {
+                color.hash(state);
+                hash_floats(&[*opacity], state);
+            }
+            Fill::Hatches(color, angle, thickness, spacing) => {
+                (color, angle).hash(state);
+                hash_floats(&[*thickness, *spacing], state);
+            }
+            Fill::Dotted(color, diameter, spacing) => {
+                color.hash(state);
+                hash_floats(&[*diameter, *spacing], state);
+            }
+            Fill::LinearGradient(stops, angle) => (stops, angle).hash(state),
+            Fill::RadialGradient(stops, (x, y)) => {
+                stops.hash(state);
+                hash_floats(&[*x, *y], state);
+            }
+        }
+    }
 }
 
 impl Color {
@@ -36,16 +104,25 @@ pub trait FillOperations {
 impl FillOperations for Fill {
     fn opacify(&self, opacity: f32) -> Self {
         match self {
-            Fill::Solid(color) => Fill::Translucent(*color, opacity),
-            Fill::Translucent(color, _) => Fill::Translucent(*color, opacity),
-            _ => *self,
+            Fill::Solid(color) => Fill::Translucent(color.clone(), opacity),
+            Fill::Translucent(color, _) => {
+                Fill::Translucent(color.clone(), opacity)
+            }
+            // stops can have different opacities, so they are all scaled
+            Fill::LinearGradient(stops, angle) => {
+                Fill::LinearGradient(opacified(stops, opacity), *angle)
+            }
+            Fill::RadialGradient(stops, focal_point) => {
+                Fill::RadialGradient(opacified(stops, opacity), *focal_point)
+            }
+            Fill::Hatches(..) | Fill::Dotted(..) => self.clone(),
         }
     }
 }
 
 impl FillOperations for Option<Fill> {
     fn opacify(&self, opacity: f32) -> Self {
-        self.map(|fill| fill.opacify(opacity))
+        self.as_ref().map(|fill| fill.opacify(opacity))
     }
 }
 
@@ -54,12 +131,46 @@ impl Fill {
         Fill::Hatches(color, Angle(45.0), thickness, spacing)
     }
 
+    /// Linear gradient going through colors evenly spaced along it
+    pub fn linear_gradient(colors: Vec<Color>, angle: Angle) -> Self {
+        Fill::LinearGradient(evenly_spaced(colors), angle)
+    }
+
+    /// Radial gradient going from the center outwards, through colors evenly spaced along it
+    pub fn radial_gradient(colors: Vec<Color>) -> Self {
+        Fill::RadialGradient(evenly_spaced(colors), (0.5, 0.5))
+    }
+
+    /// Colors the fill is painted with, including the ones of gradient stops
+    pub fn colors(&self) -> Vec<&Color> {
+        match self {
+            Fill::Solid(color)
+            | Fill::Translucent(color, _)
+            | Fill::Hatches(color, ..)
+            | Fill::Dotted(color, ..) => vec![color],
+            Fill::LinearGradient(stops, _) | Fill::RadialGradient(stops, _) => {
+                stops.iter().map(|stop| &stop.color).collect()
+            }
+        }
+    }
+
+    /// Whether the fill is painted by a definition referenced with `pattern_id`
+    pub fn needs_definition(&self) -> bool {
+        matches!(
+            self,
+            Fill::Hatches(..)
+                | Fill::Dotted(..)
+                | Fill::LinearGradient(..)
+                | Fill::RadialGradient(..)
+        )
+    }
+
     pub fn pattern_id(&self) -> String {
         if let Fill::Hatches(color, angle, thickness, spacing) = self {
             return format!(
                 "pattern-hatched-{}-{}-{}-{}",
                 angle,
-                color.name(),
+                color_id(color),
                 thickness,
                 spacing
             );
@@ -67,11 +178,22 @@ impl Fill {
         if let Fill::Dotted(color, diameter, spacing) = self {
             return format!(
                 "pattern-dotted-{}-{}-{}",
-                color.name(),
+                color_id(color),
                 diameter,
                 spacing
             );
         }
+        if let Fill::LinearGradient(stops, angle) = self {
+            return format!("gradient-linear-{}-{}", angle, stops_id(stops));
+        }
+        if let Fill::RadialGradient(stops, (focal_x, focal_y)) = self {
+            return format!(
+                "gradient-radial-{}-{}-{}",
+                focal_x,
+                focal_y,
+                stops_id(stops)
+            );
+        }
         String::from("")
     }
 
@@ -95,7 +217,7 @@ impl Fill {
                     )
                     // https://stackoverflow.com/a/55104220/9943464
                     .wrapping(vec![
-                        svg::tag("polygon").fill(*color, colormapping).attr(
+                        svg::tag("polygon").fill(color, colormapping).attr(
                             "points",
                             format!(
                                 "0,0 {},0 0,{}",
@@ -103,7 +225,7 @@ impl Fill {
                                 thickness / 2.0
                             ),
                         ),
-                        svg::tag("polygon").fill(*color, colormapping).attr(
+                        svg::tag("polygon").fill(color, colormapping).attr(
                             "points",
                             format!(
                                 "0,{} {},0 {},{} {},{}",
@@ -130,7 +252,7 @@ impl Fill {
                     .attr("viewBox", format!("0,0,{},{}", box_size, box_size))
                     .wrapping(vec![
                         svg::tag("circle")
-                            .fill(*color, colormapping)
+                            .fill(color, colormapping)
                             .attr("cx", box_size / 2.0)
                             .attr("cy", box_size / 2.0)
                             .attr("r", diameter / 2.0),
@@ -139,7 +261,239 @@ impl Fill {
 
                 Some(pattern)
             }
+            Fill::LinearGradient(stops, angle) => {
+                // the gradient's vector goes through the center of the bounding box
+                let (cos, sin) = angle.cos_sin();
+                let gradient = svg::tag("linearGradient")
+                    .attr("id", self.pattern_id())
+                    .attr("x1", 0.5 - cos / 2.0)
+                    .attr("y1", 0.5 - sin / 2.0)
+                    .attr("x2", 0.5 + cos / 2.0)
+                    .attr("y2", 0.5 + sin / 2.0)
+                    .wrapping(render_stops(stops, colormapping))
+                    .node();
+
+                Some(gradient)
+            }
+            Fill::RadialGradient(stops, (focal_x, focal_y)) => {
+                let gradient = svg::tag("radialGradient")
+                    .attr("id", self.pattern_id())
+                    .attr("cx", 0.5)
+                    .attr("cy", 0.5)
+                    .attr("r", 0.5)
+                    .attr("fx", focal_x)
+                    .attr("fy", focal_y)
+                    .wrapping(render_stops(stops, colormapping))
+                    .node();
+
+                Some(gradient)
+            }
             _ => None,
         }
     }
 }
+
+fn evenly_spaced(colors: Vec<Color>) -> Vec<GradientStop> {
+    let last = colors.len().saturating_sub(1).max(1) as f32;
+    colors
+        .into_iter()
+        .enumerate()
+        .map(|(i, color)| (i as f32 / last, color).into())
+        .collect()
+}
+
+fn opacified(stops: &[GradientStop], opacity: f32) -> Vec<GradientStop> {
+    stops
+        .iter()
+        .map(|stop| GradientStop {
+            opacity: stop.opacity * opacity,
+            ..stop.clone()
+        })
+        .collect()
+}
+
+/// Name of the color, without the `#` of exact colors, which can't be in ids
+fn color_id(color: &Color) -> String {
+    color.name().replace('#', "")
+}
+
+fn stops_id(stops: &[GradientStop]) -> String {
+    stops
+        .iter()
+        .map(|stop| {
+            format!("{}-{}-{}", stop.offset, color_id(&stop.color), stop.opacity)
+        })
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+fn render_stops(
+    stops: &[GradientStop],
+    colormapping: &ColorMapping,
+) -> Vec<svg::Element> {
+    stops
+        .iter()
+        .map(|stop| {
+            svg::tag("stop")
+                .attr("offset", stop.offset)
+                .attr("stop-color", stop.color.render(colormapping))
+                .attr("stop-opacity", stop.opacity)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Canvas, Point, Shape, Stroke};
+
+    fn definition(fill: &Fill) -> String {
+        fill.pattern_definition(&ColorMapping::default())
+            .unwrap()
+            .to_string()
+    }
+
+    #[test]
+    fn opacifying_gradients_scales_their_stops() {
+        let gradient = Fill::LinearGradient(
+            vec![(0.0, Color::Red).into(), (1.0, Color::Blue, 0.5).into()],
+            Angle(0.0),
+        );
+        let Fill::LinearGradient(stops, _) = gradient.opacify(0.5) else {
+            panic!("opacifying changed the kind of fill");
+        };
+        assert_eq!(
+            stops.iter().map(|stop| stop.opacity).collect::<Vec<_>>(),
+            vec![0.5, 0.25]
+        );
+    }
+
+    #[test]
+    fn evenly_spaced_stops() {
+        let Fill::LinearGradient(stops, _) = Fill::linear_gradient(
+            vec![Color::Red, Color::Green, Color::Blue],
+            Angle(0.0),
+        ) else {
+            unreachable!()
+        };
+        let offsets: Vec<_> = stops.iter().map(|stop| stop.offset).collect();
+        assert_eq!(offsets, vec![0.0, 0.5, 1.0]);
+        assert!(stops.iter().all(|stop| stop.opacity == 1.0));
+
+        let Fill::RadialGradient(stops, focal_point) =
+            Fill::radial_gradient(vec![Color::Red])
+        else {
+            unreachable!()
+        };
+        assert_eq!(stops, vec![GradientStop::from((0.0, Color::Red))]);
+        assert_eq!(focal_point, (0.5, 0.5));
+    }
+
+    #[test]
+    fn gradient_ids_depend_on_every_parameter() {
+        let stops =
+            vec![(0.0, Color::Red).into(), (1.0, Color::Blue, 0.5).into()];
+        let linear = Fill::LinearGradient(stops.clone(), Angle(0.0));
+
+        assert!(linear.needs_definition());
+        assert_eq!(
+            linear.pattern_id(),
+            Fill::LinearGradient(stops.clone(), Angle(0.0)).pattern_id()
+        );
+        assert_ne!(
+            linear.pattern_id(),
+            Fill::LinearGradient(stops.clone(), Angle(90.0)).pattern_id()
+        );
+        assert_ne!(
+            linear.pattern_id(),
+            Fill::LinearGradient(stops[..1].to_vec(), Angle(0.0)).pattern_id()
+        );
+
+        let radial = Fill::RadialGradient(stops.clone(), (0.5, 0.5));
+        assert_ne!(radial.pattern_id(), linear.pattern_id());
+        assert_ne!(
+            radial.pattern_id(),
+            Fill::RadialGradient(stops, (0.2, 0.5)).pattern_id()
+        );
+    }
+
+    #[test]
+    fn gradient_definitions() {
+        let linear = Fill::LinearGradient(
+            vec![(0.0, Color::Red).into(), (1.0, Color::Blue, 0.5).into()],
+            Angle(0.0),
+        );
+        let rendered = definition(&linear);
+        assert!(rendered.starts_with("<linearGradient"));
+        assert!(rendered.contains(&format!("id=\"{}\"", linear.pattern_id())));
+        assert!(rendered.contains("x1=\"0\""));
+        assert!(rendered.contains("x2=\"1\""));
+        assert_eq!(rendered.matches("<stop").count(), 2);
+        assert!(rendered.contains("stop-opacity=\"0.5\""));
+
+        let radial =
+            Fill::RadialGradient(vec![(0.0, Color::Red).into()], (0.25, 0.75));
+        let rendered = definition(&radial);
+        assert!(rendered.starts_with("<radialGradient"));
+        assert!(rendered.contains("fx=\"0.25\""));
+        assert!(rendered.contains("fy=\"0.75\""));
+    }
+
+    #[test]
+    fn shared_gradients_are_defined_once() {
+        let gradient =
+            Fill::linear_gradient(vec![Color::Black, Color::White], Angle(0.0));
+        let mut canvas = Canvas::new(4, 4);
+        let root = canvas.root();
+        root.set(
+            "first",
+            Shape::Dot(Point::Corner(0, 0)).filled(gradient.clone()),
+        );
+        root.set(
+            "second",
+            Shape::Dot(Point::Corner(1, 1)).filled(gradient.clone()),
+        );
+        root.set(
+            "outlined",
+            Shape::Rectangle(Point::Corner(2, 2), Point::Corner(3, 3))
+                .filled(Color::Red.solid())
+                .stroked(Stroke::new(gradient.clone(), 2.0)),
+        );
+        root.set(
+            "third",
+            Shape::Dot(Point::Corner(2, 0))
+                .filled(Fill::radial_gradient(vec![Color::Red, Color::Blue])),
+        );
+
+        let ids: Vec<_> = canvas
+            .unique_pattern_fills()
+            .iter()
+            .map(Fill::pattern_id)
+            .collect();
+        assert_eq!(ids.len(), 2);
+        assert!(ids.contains(&gradient.pattern_id()));
+    }
+
+    #[test]
+    fn gradients_are_rasterized() {
+        let mut canvas = Canvas::new(2, 1);
+        canvas.root().set(
+            "gradient",
+            Shape::Rectangle(Point::Corner(0, 0), Point::Corner(2, 1)).filled(
+                Fill::linear_gradient(
+                    vec![Color::Black, Color::White],
+                    Angle(0.0),
+                ),
+            ),
+        );
+
+        let pixmap = canvas.render_to_pixmap(100, 50).unwrap();
+        let brightness = |x: u32| {
+            let pixel = pixmap.pixel(x, 25).unwrap();
+            assert_eq!(pixel.alpha(), 255);
+            pixel.red() as u32 + pixel.green() as u32 + pixel.blue() as u32
+        };
+        assert!(brightness(5) < brightness(50));
+        assert!(brightness(50) < brightness(95));
+    }
+}
@@ -0,0 +1,292 @@
+use super::path::{Anchor, closed_polyline, flatten_path};
+use super::shapes::{point_on_circle, quadratic_curve_point};
+use crate::{Angle, Region, Shape};
+
+/// Number of vertices both outlines are resampled to when morphing
+const MORPH_RESOLUTION: usize = 128;
+
+/// Number of straight lines used to approximate each curve of an outline
+pub(crate) const CURVE_STEPS: usize = 16;
+
+impl Shape {
+    /// Closed outline of the shape as a list of vertices, in grid units. Curves are approximated with straight lines.
+    /// Shapes without a closed outline, such as lines, text or dots, return `None`.
+    pub fn outline(&self) -> Option<Vec<(f32, f32)>> {
+        let ellipse = |(x, y): (f32, f32), (rx, ry): (f32, f32)| {
+            (0..MORPH_RESOLUTION)
+                .map(|i| {
+                    let (cos, sin) =
+                        Angle::from_ratio(i as f32, MORPH_RESOLUTION as f32)
+                            .cos_sin();
+                    (x + rx * cos, y + ry * sin)
+                })
+                .collect::<Vec<_>>()
+        };
+
+        let outline = match self {
+            Shape::Polygon(start, lines) => {
+                let mut vertices = vec![start.grid_coords()];
+                let mut from = *start;
+                for line in lines {
+                    let end = line.end().grid_coords();
+                    match line.control_point(from) {
+                        Some(control) => {
+                            vertices.extend((1..=CURVE_STEPS).map(|i| {
+                                quadratic_curve_point(
+                                    from.grid_coords(),
+                                    control,
+                                    end,
+                                    i as f32 / CURVE_STEPS as f32,
+                                )
+                            }))
+                        }
+                        None => vertices.push(end),
+                    }
+                    from = line.end();
+                }
+                vertices
+            }
+            // only the first subpath is considered, e.g. the outer contour of a glyph
+            Shape::Path(segments) => {
+                flatten_path(segments, CURVE_STEPS).into_iter().next()?
+            }
+            Shape::Rectangle(start, end) => {
+                let (x, y) = start.grid_coords();
+                let (w, h) = Region::from((*start, *end)).size(1);
+                vec![(x, y), (x + w, y), (x + w, y + h), (x, y + h)]
+            }
+            Shape::BigCircle(at) => {
+                ellipse(at.as_centered().grid_coords(), (0.5, 0.5))
+            }
+            Shape::Ellipse(region) => {
+                let (x, y) = region.start.grid_coords();
+                let (w, h) = region.size(1);
+                ellipse((x + w / 2.0, y + h / 2.0), (w / 2.0, h / 2.0))
+            }
+            Shape::Sector {
+                center,
+                inner_radius_cells,
+                outer_radius_cells,
+                start,
+                end,
+            } => {
+                let center = center.grid_coords();
+                let steps = MORPH_RESOLUTION / 2;
+                let along = |radius: f32, from: Angle, to: Angle| {
+                    (0..=steps).map(move |i| {
+                        let progress = i as f32 / steps as f32;
+                        point_on_circle(
+                            center,
+                            radius,
+                            Angle::from_degrees(
+                                from.degrees() + (to - from).degrees() * progress,
+                            ),
+                        )
+                    })
+                };
+
+                along(*outer_radius_cells, *start, *end)
+                    .chain(along(*inner_radius_cells, *end, *start))
+                    .collect()
+            }
+            _ => return None,
+        };
+
+        (!outline.is_empty()).then_some(outline)
+    }
+
+    /// Morphs this shape into `other`, `t` going from 0 (this shape) to 1 (`other`).
+    /// Both outlines are resampled to the same number of vertices, which are then interpolated linearly, giving a `Path`.
+    /// Shapes without an outline (see `Shape::outline`) switch from one to the other halfway through.
+    pub fn interpolate(&self, other: &Shape, t: f32) -> Shape {
+        if t <= 0.0 {
+            return self.clone();
+        }
+        if t >= 1.0 {
+            return other.clone();
+        }
+
+        match (self.outline(), other.outline()) {
+            (Some(from), Some(to)) => {
+                let from = resample(&from, MORPH_RESOLUTION);
+                let to = aligned(&from, resample(&to, MORPH_RESOLUTION));
+
+                closed_polyline(from.iter().zip(to.iter()).map(
+                    |((x1, y1), (x2, y2))| {
+                        Anchor::at_coords((
+                            x1 + (x2 - x1) * t,
+                            y1 + (y2 - y1) * t,
+                        ))
+                    },
+                ))
+            }
+            _ if t < 0.5 => self.clone(),
+            _ => other.clone(),
+        }
+    }
+}
+
+/// Evenly spaced vertices along a closed outline
+fn resample(vertices: &[(f32, f32)], count: usize) -> Vec<(f32, f32)> {
+    let edges: Vec<_> = vertices
+        .iter()
+        .copied()
+        .zip(vertices.iter().copied().cycle().skip(1))
+        .collect();
+    let lengths: Vec<f32> = edges
+        .iter()
+        .map(|(a, b)| (b.0 - a.0).hypot(b.1 - a.1))
+        .collect();
+
+    let perimeter: f32 = lengths.iter().sum();
+    if perimeter == 0.0 {
+        return vec![vertices[0]; count];
+    }
+
+    let mut edge = 0;
+    let mut walked = 0.0;
+
+    (0..count)
+        .map(|i| {
+            let target = perimeter * i as f32 / count as f32;
+            while edge < edges.len() - 1 && walked + lengths[edge] < target {
+                walked += lengths[edge];
+                edge += 1;
+            }
+
+            let ((x1, y1), (x2, y2)) = edges[edge];
+            let progress = if lengths[edge] == 0.0 {
+                0.0
+            } else {
+                (target - walked) / lengths[edge]
+            };
+            (x1 + (x2 - x1) * progress, y1 + (y2 - y1) * progress)
+        })
+        .collect()
+}
+
+/// Reorders `to`'s vertices so that they go in the same direction as `from`'s, starting from the closest one
+fn aligned(from: &[(f32, f32)], mut to: Vec<(f32, f32)>) -> Vec<(f32, f32)> {
+    let signed_area = |vertices: &[(f32, f32)]| {
+        vertices
+            .iter()
+            .zip(vertices.iter().cycle().skip(1))
+            .map(|(a, b)| a.0 * b.1 - b.0 * a.1)
+            .sum::<f32>()
+    };
+
+    if signed_area(from) * signed_area(&to) < 0.0 {
+        to.reverse();
+    }
+
+    let distance = |offset: usize| -> f32 {
+        from.iter()
+            .enumerate()
+            .map(|(i, a)| {
+                let b = to[(i + offset) % to.len()];
+                (b.0 - a.0).powi(2) + (b.1 - a.1).powi(2)
+            })
+            .sum()
+    };
+
+    let best_offset = (0..to.len())
+        .min_by(|&a, &b| distance(a).total_cmp(&distance(b)))
+        .unwrap_or(0);
+
+    to.rotate_left(best_offset);
+    to
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Point;
+
+    fn square(x: usize, y: usize) -> Shape {
+        Shape::Rectangle(Point::Corner(x, y), Point::Corner(x + 2, y + 2))
+    }
+
+    #[test]
+    fn interpolation_ends_on_both_shapes() {
+        let (from, to) = (square(0, 0), square(4, 0));
+        assert!(matches!(
+            from.interpolate(&to, 0.0),
+            Shape::Rectangle(start, _) if start == Point::Corner(0, 0)
+        ));
+        assert!(matches!(
+            from.interpolate(&to, 1.0),
+            Shape::Rectangle(start, _) if start == Point::Corner(4, 0)
+        ));
+    }
+
+    #[test]
+    fn interpolated_outlines_are_in_between() {
+        let bounds = |shape: &Shape| {
+            let outline = shape.outline().unwrap();
+            let xs = outline.iter().map(|(x, _)| *x);
+            let ys = outline.iter().map(|(_, y)| *y);
+            [
+                xs.clone().fold(f32::MAX, f32::min),
+                ys.clone().fold(f32::MAX, f32::min),
+                xs.fold(f32::MIN, f32::max),
+                ys.fold(f32::MIN, f32::max),
+            ]
+        };
+
+        let (from, to) = (square(0, 0), square(4, 0));
+        let halfway = from.interpolate(&to, 0.5);
+        assert!(matches!(halfway, Shape::Path(..)));
+
+        for ((bound, from), to) in bounds(&halfway)
+            .into_iter()
+            .zip(bounds(&from))
+            .zip(bounds(&to))
+        {
+            assert!((bound - (from + to) / 2.0).abs() < 1e-3);
+        }
+    }
+
+    #[test]
+    fn shapes_without_outlines_switch_halfway() {
+        let (from, to) = (
+            Shape::Dot(Point::Corner(0, 0)),
+            Shape::Dot(Point::Corner(2, 2)),
+        );
+        assert!(matches!(
+            from.interpolate(&to, 0.4),
+            Shape::Dot(at) if at == Point::Corner(0, 0)
+        ));
+        assert!(matches!(
+            from.interpolate(&to, 0.6),
+            Shape::Dot(at) if at == Point::Corner(2, 2)
+        ));
+    }
+
+    #[test]
+    fn resampled_vertices_are_evenly_spaced() {
+        let vertices =
+            resample(&[(0.0, 0.0), (4.0, 0.0), (4.0, 4.0), (0.0, 4.0)], 8);
+        assert_eq!(
+            vertices,
+            vec![
+                (0.0, 0.0),
+                (2.0, 0.0),
+                (4.0, 0.0),
+                (4.0, 2.0),
+                (4.0, 4.0),
+                (2.0, 4.0),
+                (0.0, 4.0),
+                (0.0, 2.0),
+            ]
+        );
+        assert_eq!(resample(&[(1.0, 1.0)], 3), vec![(1.0, 1.0); 3]);
+    }
+
+    #[test]
+    fn aligned_outlines_go_the_same_way() {
+        let from = [(0.0, 0.0), (1.0, 0.0), (1.0, 1.0), (0.0, 1.0)];
+        // counter-clockwise, starting elsewhere
+        let to = vec![(1.0, 1.0), (1.0, 0.0), (0.0, 0.0), (0.0, 1.0)];
+        assert_eq!(aligned(&from, to), from.to_vec());
+    }
+}
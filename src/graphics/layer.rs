@@ -1,15 +1,66 @@
 use itertools::Itertools;
 
-use crate::{Fill, Filter, Object, ObjectSizes, Point, Region, Toggleable};
-use std::{collections::HashMap, fmt::Display};
+use anyhow::{Result, anyhow};
+
+use crate::{
+    Clip, Fill, Filter, Object, ObjectSizes, Point, Region, SpatialIndex,
+    Toggleable, Transformation, graphics::IndexEntry,
+};
+use std::{
+    collections::{HashMap, HashSet},
+    fmt::Display,
+};
+
+/// How a layer is composited with the layers below it, see CSS's `mix-blend-mode`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BlendMode {
+    #[default]
+    Normal,
+    Multiply,
+    Screen,
+    Overlay,
+    Darken,
+    Lighten,
+    ColorDodge,
+    ColorBurn,
+    HardLight,
+    SoftLight,
+    Difference,
+    Exclusion,
+    Hue,
+    Saturation,
+    Color,
+    Luminosity,
+}
 
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone)]
 // #[wasm_bindgen(getter_with_clone)]
 pub struct Layer {
     pub object_sizes: ObjectSizes,
-    pub objects: HashMap<String, Object>,
+    objects: HashMap<String, Object>,
     pub name: String,
     pub hidden: bool,
+    /// Opacity of the layer as a whole, from 0 to 1. Overlapping objects don't add up, unlike with translucent fills.
+    pub opacity: f32,
+    pub blend_mode: BlendMode,
+    /// Applied to the whole layer, around its center
+    pub transformations: Vec<Transformation>,
+    /// Applied to the whole layer, after its objects are composited together
+    pub filters: Vec<Filter>,
+    /// Area the whole layer is clipped to
+    pub clip_to: Option<Clip>,
+    /// Groups nested in this layer, drawn above its objects. The first one is on top.
+    /// They inherit the transformations, filters and clipping of this layer.
+    pub layers: Vec<Layer>,
+    pub(crate) index: SpatialIndex,
+    /// `z_index` of the next object added on top, `None` when it needs to be computed again
+    next_z_index: Option<i32>,
+}
+
+impl Default for Layer {
+    fn default() -> Self {
+        Layer::new("")
+    }
 }
 
 impl Layer {
@@ -19,9 +70,85 @@ impl Layer {
             objects: HashMap::new(),
             name: format!("{}", name),
             hidden: false,
+            opacity: 1.0,
+            blend_mode: BlendMode::default(),
+            transformations: vec![],
+            filters: vec![],
+            clip_to: None,
+            layers: vec![],
+            index: SpatialIndex::default(),
+            next_z_index: Some(0),
         }
     }
 
+    /// Layer holding the given objects, indexed on the first query
+    pub(crate) fn with_objects(
+        name: impl Display,
+        objects: HashMap<String, Object>,
+    ) -> Self {
+        let mut layer = Layer::new(name);
+        layer.objects = objects;
+        layer.index.invalidate_all();
+        layer.next_z_index = None;
+        layer
+    }
+
+    /// Nested group at the given path, relative to this layer, such as `"left/meter"`
+    pub fn group(&mut self, path: &str) -> Result<&mut Layer> {
+        path.split('/').try_fold(self, |layer, name| {
+            let parent = layer.name.clone();
+            layer
+                .layers
+                .iter_mut()
+                .find(|group| group.name == name)
+                .ok_or_else(|| {
+                    anyhow!("Group {name} does not exist in layer {parent}")
+                })
+        })
+    }
+
+    pub fn find_group(&self, path: &str) -> Option<&Layer> {
+        path.split('/').try_fold(self, |layer, name| {
+            layer.layers.iter().find(|group| group.name == name)
+        })
+    }
+
+    pub fn group_exists(&self, path: &str) -> bool {
+        self.find_group(path).is_some()
+    }
+
+    /// Creates an empty group nested directly in this layer, on top of the existing ones
+    pub fn new_group(&mut self, name: &str) -> &mut Layer {
+        if self.group_exists(name) {
+            panic!("Group {} already exists in layer {}", name, self.name);
+        }
+
+        let mut group = Layer::new(name);
+        group.object_sizes = self.object_sizes;
+        self.layers.insert(0, group);
+        self.layers.first_mut().unwrap()
+    }
+
+    pub fn add_group(&mut self, group: Layer) -> &mut Layer {
+        if self.group_exists(&group.name) {
+            panic!("Group {} already exists in layer {}", group.name, self.name);
+        }
+
+        self.layers.insert(0, group);
+        self.layers.first_mut().unwrap()
+    }
+
+    pub fn remove_group(&mut self, name: &str) {
+        self.layers.retain(|group| group.name != name);
+    }
+
+    /// This layer and all the groups nested in it, recursively
+    pub fn walk(&self) -> Vec<&Layer> {
+        std::iter::once(self)
+            .chain(self.layers.iter().flat_map(|group| group.walk()))
+            .collect()
+    }
+
     pub fn hide(&mut self) {
         self.hidden = true;
     }
@@ -39,56 +166,205 @@ impl Layer {
     }
 
     pub fn safe_object(&mut self, name: &str) -> Option<&mut Object> {
+        self.index.invalidate(name);
         self.objects.get_mut(name)
     }
 
+    pub fn objects(&self) -> &HashMap<String, Object> {
+        &self.objects
+    }
+
+    /// Any object can be changed through the map, so the whole spatial index is rebuilt on the next query
+    pub fn objects_mut(&mut self) -> &mut HashMap<String, Object> {
+        self.index.invalidate_all();
+        self.next_z_index = None;
+        &mut self.objects
+    }
+
     pub fn objects_sorted_owned(
         &self,
     ) -> impl Iterator<Item = (String, Object)> + '_ {
-        self.objects
-            .iter()
-            .sorted_by_cached_key(|&(id, _)| id.clone())
+        self.objects_sorted()
             .map(|(id, obj)| (id.clone(), obj.clone()))
     }
 
-    // Useful to be able to guarantee a stable order when rendering.
+    /// Objects from the bottom-most to the top-most one, i.e. in rendering order.
+    /// Objects are sorted by their `z_index`, then by name.
     pub fn objects_sorted(&self) -> impl Iterator<Item = (&String, &Object)> {
-        self.objects
-            .iter()
-            .sorted_by_cached_key(|&(id, _)| id.clone())
+        self.objects.iter().sorted_by_cached_key(|&(id, obj)| {
+            (obj.z_index.unwrap_or_default(), id.clone())
+        })
     }
 
-    // Useful to be able to guarantee a stable order when rendering.
+    /// Same order as `objects_sorted`
     pub fn objects_sorted_mut(
         &mut self,
     ) -> impl Iterator<Item = (&String, &mut Object)> {
-        self.objects
-            .iter_mut()
-            .sorted_by_cached_key(|&(id, _)| id.clone())
+        let names = self.objects.keys().cloned().collect();
+        self.objects_named_mut(names)
     }
 
+    /// Objects within the region, in the same order as `objects_sorted`
     pub fn objects_in(
         &mut self,
         region: Region,
     ) -> impl Iterator<Item = (&String, &mut Object)> {
+        let names =
+            self.indexed_names(&region, |entry| entry.region.within(&region));
+        self.objects_named_mut(names)
+    }
+
+    /// Objects sharing at least one cell with the region, in the same order as `objects_sorted`
+    pub fn objects_intersecting(
+        &mut self,
+        region: Region,
+    ) -> impl Iterator<Item = (&String, &mut Object)> {
+        let names =
+            self.indexed_names(&region, |entry| entry.region.intersects(&region));
+        self.objects_named_mut(names)
+    }
+
+    /// Other objects whose region overlaps the region of the given one, in the same order as `objects_sorted`
+    pub fn objects_overlapping(
+        &mut self,
+        name: &str,
+    ) -> impl Iterator<Item = (&String, &mut Object)> {
+        self.ensure_object_exists(name);
+        self.reindex();
+
+        let region = self.index.entry(name).unwrap().region;
+        let mut names =
+            self.indexed_names(&region, |entry| entry.region.intersects(&region));
+        names.remove(name);
+        self.objects_named_mut(names)
+    }
+
+    /// Object whose region is the closest to the point. Ties go to the top-most object.
+    pub fn nearest_object(
+        &mut self,
+        point: Point,
+    ) -> Option<(&String, &mut Object)> {
+        self.reindex();
+
+        let names = self.index.nearest(point).into_iter().cloned().collect();
+        self.objects_named_mut(names).last()
+    }
+
+    /// Updates the spatial index used by `objects_in`, `object_at` and the like.
+    /// Queries call it themselves, so it only saves them from doing it later.
+    pub fn reindex(&mut self) {
+        self.index.refresh(&self.objects);
+    }
+
+    /// Names of the objects possibly touching the region, according to the spatial index, that match the predicate
+    fn indexed_names(
+        &mut self,
+        region: &Region,
+        predicate: impl Fn(&IndexEntry) -> bool,
+    ) -> HashSet<String> {
+        self.reindex();
+        self.index
+            .candidates(region)
+            .filter(|(_, entry)| predicate(entry))
+            .map(|(name, _)| name.clone())
+            .collect()
+    }
+
+    /// Objects with the given names, in the same order as `objects_sorted`
+    fn objects_named_mut(
+        &mut self,
+        names: HashSet<String>,
+    ) -> impl Iterator<Item = (&String, &mut Object)> {
+        for name in &names {
+            self.index.invalidate(name);
+        }
+
         self.objects
             .iter_mut()
-            .filter(move |(_, obj)| obj.shape.region().within(&region))
+            .filter(move |(id, _)| names.contains(*id))
+            .sorted_by_cached_key(|(id, obj)| {
+                (obj.z_index.unwrap_or_default(), (*id).clone())
+            })
+    }
+
+    /// Names of the objects, from the bottom-most to the top-most one
+    pub fn stacking_order(&self) -> Vec<String> {
+        self.objects_sorted().map(|(id, _)| id.clone()).collect()
+    }
+
+    /// Moves the object to that position in the stacking order, 0 being the bottom.
+    /// All objects of the layer get their `z_index` reassigned to match the new order.
+    pub fn move_to_index(&mut self, name: &str, index: usize) {
+        self.ensure_object_exists(name);
+
+        let mut order = self.stacking_order();
+        order.retain(|id| id != name);
+        order.insert(index.min(order.len()), name.to_string());
+
+        for (z_index, id) in order.iter().enumerate() {
+            self.objects.get_mut(id).unwrap().z_index = Some(z_index as i32);
+        }
+        self.next_z_index = Some(order.len() as i32);
     }
 
+    pub fn bring_to_front(&mut self, name: &str) {
+        self.move_to_index(name, usize::MAX);
+    }
+
+    pub fn send_to_back(&mut self, name: &str) {
+        self.move_to_index(name, 0);
+    }
+
+    /// Moves the object right above `other`
+    pub fn move_above(&mut self, name: &str, other: &str) {
+        if name != other {
+            self.move_to_index(name, self.index_without(other, name) + 1);
+        }
+    }
+
+    /// Moves the object right below `other`
+    pub fn move_below(&mut self, name: &str, other: &str) {
+        if name != other {
+            self.move_to_index(name, self.index_without(other, name));
+        }
+    }
+
+    /// Position of `other` in the stacking order, once `name` is taken out of it
+    fn index_without(&self, other: &str, name: &str) -> usize {
+        self.ensure_object_exists(other);
+        self.stacking_order()
+            .iter()
+            .filter(|id| *id != name)
+            .position(|id| id == other)
+            .unwrap()
+    }
+
+    fn ensure_object_exists(&self, name: &str) {
+        if !self.objects.contains_key(name) {
+            panic!("object {} does not exist in layer {}", name, self.name);
+        }
+    }
+
+    /// Top-most object positioned at that point
     pub fn object_at(&mut self, point: Point) -> Option<&mut Object> {
-        self.find_object_mut(|o| o.position() == point.as_corner())
+        let names = self.indexed_names(&Region::from((point, point)), |entry| {
+            entry.position == point.as_corner()
+        });
+        self.objects_named_mut(names).last().map(|(_, obj)| obj)
     }
 
     pub fn has_object_that(&self, pred: impl Fn(&Object) -> bool) -> bool {
-        self.objects.values().any(|obj| pred(obj))
+        self.objects.values().any(pred)
     }
 
     pub fn find_object_mut(
         &mut self,
         pred: impl Fn(&Object) -> bool,
     ) -> Option<&mut Object> {
-        self.objects.values_mut().find(|obj| pred(obj))
+        let (name, object) =
+            self.objects.iter_mut().find(|(_, obj)| pred(obj))?;
+        self.index.invalidate(name);
+        Some(object)
     }
 
     pub fn find_object(&self, pred: impl Fn(&Object) -> bool) -> Option<&Object> {
@@ -98,36 +374,85 @@ impl Layer {
     // Remove all objects.
     pub fn clear(&mut self) {
         self.objects.clear();
+        self.index.clear();
+        self.next_z_index = Some(0);
     }
 
     pub fn replace(&mut self, with: Layer) {
         self.objects.clone_from(&with.objects);
+        self.index = with.index;
+        self.next_z_index = with.next_z_index;
     }
 
     pub fn remove_all_objects_in(&mut self, region: &Region) {
-        self.objects.retain(|_, Object { shape: object, .. }| {
-            !object.region().within(region)
-        })
+        for name in
+            self.indexed_names(region, |entry| entry.region.within(region))
+        {
+            self.remove_object(&name);
+        }
     }
 
+    /// Paints the objects of this layer and of all its nested groups
     pub fn paint_all_objects(&mut self, fill: Fill) {
         for obj in self.objects.values_mut() {
-            obj.fill = Some(fill);
+            obj.fill = Some(fill.clone());
+        }
+        for group in self.layers.iter_mut() {
+            group.paint_all_objects(fill.clone());
         }
     }
 
+    /// Filters the objects of this layer and of all its nested groups, one by one. See `filter` to filter the layer as a whole.
     pub fn filter_all_objects(&mut self, filter: Filter) {
         for obj in self.objects.values_mut() {
             obj.filters.push(filter)
         }
+        for group in self.layers.iter_mut() {
+            group.filter_all_objects(filter);
+        }
+    }
+
+    pub fn set_opacity(&mut self, opacity: f32) {
+        self.opacity = opacity;
+    }
+
+    pub fn set_blend_mode(&mut self, blend_mode: BlendMode) {
+        self.blend_mode = blend_mode;
+    }
+
+    pub fn transform(&mut self, transformation: Transformation) {
+        self.transformations.push(transformation);
+    }
+
+    pub fn clear_transformations(&mut self) {
+        self.transformations.clear();
+    }
+
+    pub fn filter(&mut self, filter: Filter) {
+        self.filters.push(filter);
+    }
+
+    pub fn clear_filters(&mut self) {
+        self.filters.clear();
     }
 
+    pub fn set_clip(&mut self, clip: impl Into<Clip>) {
+        self.clip_to = Some(clip.into());
+    }
+
+    pub fn clear_clip(&mut self) {
+        self.clip_to = None;
+    }
+
+    /// Moves the objects of this layer and of all its nested groups
     pub fn move_all_objects(&mut self, dx: i32, dy: i32) {
-        self.objects
-            .iter_mut()
-            .for_each(|(_, Object { shape: object, .. })| {
-                object.translate(dx, dy)
-            });
+        for (name, object) in self.objects.iter_mut() {
+            object.shape.translate(dx, dy);
+            self.index.invalidate(name);
+        }
+        for group in self.layers.iter_mut() {
+            group.move_all_objects(dx, dy);
+        }
     }
 
     pub fn add(&mut self, name: impl Display, object: impl Into<Object>) {
@@ -140,6 +465,18 @@ impl Layer {
         self.set(name_str, object);
     }
 
+    /// Adds the object at that position in the stacking order, see `move_to_index`
+    pub fn add_at_index(
+        &mut self,
+        index: usize,
+        name: impl Display,
+        object: impl Into<Object>,
+    ) {
+        let name_str = format!("{}", name);
+        self.add(&name_str, object);
+        self.move_to_index(&name_str, index);
+    }
+
     pub fn add_anon(&mut self, object: impl Into<Object>) {
         self.add(format!("anon-{}", self.objects.len()), object);
     }
@@ -159,10 +496,34 @@ impl Layer {
         }
     }
 
+    /// Adds the object, or replaces the one with the same name.
+    /// Unless it was stacked with `Object::stacked_at`, a replacing object keeps the `z_index` of the one it replaces,
+    /// and a new object goes on top of the others.
     pub fn set(&mut self, name: impl Display, object: impl Into<Object>) {
         let name_str = format!("{}", name);
-
-        self.objects.insert(name_str, object.into());
+        let mut object = object.into();
+
+        let replaced = self.objects.get(&name_str).and_then(|obj| obj.z_index);
+        let z_index = match (object.z_index, replaced) {
+            (Some(z_index), _) | (None, Some(z_index)) => z_index,
+            (None, None) => self.next_z_index(),
+        };
+        object.z_index = Some(z_index);
+        self.next_z_index = Some(self.next_z_index().max(z_index + 1));
+
+        self.index.insert(&name_str, &object);
+        self.objects.insert(name_str, object);
+    }
+
+    /// `z_index` that puts an object above all the others
+    fn next_z_index(&mut self) -> i32 {
+        *self.next_z_index.get_or_insert_with(|| {
+            self.objects
+                .values()
+                .map(|obj| obj.z_index.unwrap_or_default() + 1)
+                .max()
+                .unwrap_or(0)
+        })
     }
 
     pub fn filter_object(
@@ -181,11 +542,12 @@ impl Layer {
 
     pub fn remove_object(&mut self, name: &str) {
         self.objects.remove(name);
+        self.index.remove(name);
     }
 
+    /// Replaces the object, keeping its place in the stacking order, see `set`
     pub fn replace_object(&mut self, name: &str, object: Object) {
-        self.remove_object(name);
-        self.add(name, object);
+        self.set(name, object);
     }
 
     pub fn add_objects(&mut self, objects: impl IntoIterator<Item = Object>) {
@@ -194,14 +556,19 @@ impl Layer {
         }
     }
 
+    /// Objects with the tag, in the same order as `objects_sorted`
     pub fn objects_with_tag(
         &mut self,
         tag: impl Display,
     ) -> impl Iterator<Item = (&String, &mut Object)> {
         let tag_str = format!("{}", tag);
-        self.objects
-            .iter_mut()
-            .filter(move |(_, obj)| obj.has_tag(&tag_str))
+        let names = self
+            .objects
+            .iter()
+            .filter(|(_, obj)| obj.has_tag(&tag_str))
+            .map(|(id, _)| id.clone())
+            .collect();
+        self.objects_named_mut(names)
     }
 
     pub fn tag_objects(
@@ -217,11 +584,12 @@ impl Layer {
         }
     }
 
-    /// Returns the effective region the layer occupies, by merging all its objects' regions.
+    /// Returns the effective region the layer occupies, by merging all its objects' regions, including those of nested groups.
     pub fn region(&self) -> Region {
         self.objects
             .values()
             .map(|object| object.region())
+            .chain(self.layers.iter().map(|group| group.region()))
             .fold(Region::default(), |acc, region| acc.merge(&region))
     }
 }
@@ -235,3 +603,157 @@ impl Object {
         layer.set(name, self);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Color, Point, Shape};
+
+    fn dot(x: usize, y: usize) -> Object {
+        Shape::Dot(Point::Corner(x, y)).into()
+    }
+
+    #[test]
+    fn new_objects_go_on_top() {
+        let mut layer = Layer::new("layer");
+        layer.add("b", dot(0, 0));
+        layer.add("a", dot(1, 1));
+        layer.add_anon(dot(2, 2));
+        assert_eq!(layer.stacking_order(), vec!["b", "a", "anon-2"]);
+    }
+
+    #[test]
+    fn replaced_objects_keep_their_place() {
+        let mut layer = Layer::new("layer");
+        layer.add("a", dot(0, 0));
+        layer.add("b", dot(1, 1));
+        layer.add("c", dot(2, 2));
+
+        layer.replace_object("a", dot(3, 3));
+        layer.set("b", dot(3, 3));
+        assert_eq!(layer.stacking_order(), vec!["a", "b", "c"]);
+        assert_eq!(layer.object("a").position(), Point::Corner(3, 3));
+    }
+
+    #[test]
+    fn explicit_z_indices_are_kept() {
+        let mut layer = Layer::new("layer");
+        layer.add("a", dot(0, 0));
+        layer.add("b", dot(1, 1).stacked_at(-1));
+        layer.add("c", dot(2, 2));
+        layer.set("c", dot(2, 2).stacked_at(-2));
+        assert_eq!(layer.stacking_order(), vec!["c", "b", "a"]);
+    }
+
+    #[test]
+    fn objects_can_be_stacked_at_zero() {
+        let mut layer = Layer::new("layer");
+        layer.add("b", dot(0, 0));
+        layer.add("a", dot(1, 1));
+        layer.set("a", dot(1, 1).stacked_at(0));
+        // tied with b, so sorted by name
+        assert_eq!(layer.stacking_order(), vec!["a", "b"]);
+        layer.add("c", dot(2, 2));
+        assert_eq!(layer.stacking_order(), vec!["a", "b", "c"]);
+    }
+
+    #[test]
+    fn moving_in_the_stacking_order() {
+        let mut layer = Layer::new("layer");
+        for name in ["a", "b", "c", "d"] {
+            layer.add(name, dot(0, 0));
+        }
+
+        layer.bring_to_front("a");
+        assert_eq!(layer.stacking_order(), vec!["b", "c", "d", "a"]);
+        layer.move_below("d", "b");
+        assert_eq!(layer.stacking_order(), vec!["d", "b", "c", "a"]);
+        layer.add_at_index(1, "e", dot(0, 0));
+        assert_eq!(layer.stacking_order(), vec!["d", "e", "b", "c", "a"]);
+        // added objects still go on top after reordering
+        layer.add("f", dot(0, 0));
+        assert_eq!(layer.stacking_order().last().unwrap(), "f");
+    }
+
+    #[test]
+    fn moving_all_objects_updates_the_index() {
+        let mut layer = Layer::new("layer");
+        layer.add("a", dot(0, 0));
+        layer.add("b", dot(1, 0));
+        assert_eq!(layer.objects_in(Region::from(((0, 0), (1, 1)))).count(), 2);
+
+        layer.move_all_objects(5, 5);
+        assert_eq!(layer.objects_in(Region::from(((0, 0), (1, 1)))).count(), 0);
+        assert_eq!(layer.objects_in(Region::from(((5, 5), (6, 6)))).count(), 2);
+    }
+
+    #[test]
+    fn bulk_changes_reach_nested_groups() {
+        let mut layer = Layer::new("layer");
+        layer.add("a", dot(0, 0));
+        layer
+            .new_group("hud")
+            .new_group("left")
+            .add("meter", dot(1, 1));
+
+        layer.move_all_objects(2, 2);
+        layer.paint_all_objects(Fill::Solid(Color::Red));
+        layer.filter_all_objects(Filter::glow(2.0));
+
+        let meter = layer.group("hud/left").unwrap().object("meter").clone();
+        assert_eq!(meter.position(), Point::Corner(3, 3));
+        assert!(matches!(meter.fill, Some(Fill::Solid(Color::Red))));
+        assert_eq!(meter.filters, vec![Filter::glow(2.0)]);
+        assert!(
+            layer
+                .group("hud/left")
+                .unwrap()
+                .object_at(Point::Corner(3, 3))
+                .is_some()
+        );
+    }
+
+    #[test]
+    fn objects_changed_through_iterators_are_reindexed() {
+        let mut layer = Layer::new("layer");
+        layer.add("a", dot(0, 0));
+        layer.add("b", dot(1, 1));
+        layer.object("b").tag("moving");
+
+        for (_, object) in layer.objects_with_tag("moving") {
+            object.shape.translate(5, 5);
+        }
+        assert!(layer.object_at(Point::Corner(6, 6)).is_some());
+        assert!(layer.object_at(Point::Corner(1, 1)).is_none());
+
+        for (_, object) in layer.objects_sorted_mut() {
+            object.shape.translate(1, 0);
+        }
+        assert!(layer.object_at(Point::Corner(1, 0)).is_some());
+        assert!(layer.object_at(Point::Corner(7, 6)).is_some());
+    }
+
+    #[test]
+    fn objects_replaced_through_the_map_are_reindexed() {
+        let mut layer = Layer::new("layer");
+        layer.add("a", dot(0, 0));
+        assert!(layer.object_at(Point::Corner(0, 0)).is_some());
+
+        *layer.objects_mut().get_mut("a").unwrap() = dot(4, 4);
+        assert!(layer.object_at(Point::Corner(0, 0)).is_none());
+        assert!(layer.object_at(Point::Corner(4, 4)).is_some());
+    }
+
+    #[test]
+    fn objects_with_tag_are_sorted() {
+        let mut layer = Layer::new("layer");
+        for name in ["c", "a", "b"] {
+            layer.add(name, dot(0, 0).tagged("dot"));
+        }
+        let names: Vec<_> = layer
+            .objects_with_tag("dot")
+            .map(|(id, _)| id.clone())
+            .collect();
+        assert_eq!(names, vec!["c", "a", "b"]);
+    }
+}
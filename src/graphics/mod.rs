@@ -1,18 +1,35 @@
 pub mod canvas;
+pub mod clip;
+pub mod collision;
 pub mod color;
 pub mod fill;
 pub mod filter;
+pub mod interpolation;
 pub mod layer;
 pub mod objects;
+pub mod palettes;
+pub mod path;
 pub mod region;
+pub mod rgba;
 pub mod shapes;
+pub mod spatial_index;
+pub mod stroke;
+pub mod themes;
 pub mod transform;
 
 pub use canvas::Canvas;
+pub use clip::{Clip, Mask, MaskMode};
 pub use color::{Color, ColorMapping};
-pub use fill::{Fill, FillOperations};
+pub use fill::{Fill, FillOperations, GradientStop};
 pub use filter::{Filter, FilterType};
-pub use layer::Layer;
+pub use layer::{BlendMode, Layer};
 pub use objects::{Object, ObjectSizes};
-pub use shapes::{LineSegment, Shape};
+pub use path::{Anchor, PathBuilder, PathSegment};
+pub use rgba::Rgba;
+pub use shapes::{
+    BooleanOperation, HorizontalAlignment, LineSegment, Shape, TilingOptions,
+    VerticalAlignment,
+};
+pub use spatial_index::{IndexEntry, SpatialIndex};
+pub use stroke::{LineCap, LineJoin, Stroke};
 pub use transform::{Transformation, TransformationType};
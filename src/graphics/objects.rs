@@ -1,5 +1,8 @@
 use super::shapes::Shape;
-use crate::{Angle, Fill, Filter, Point, Region, Transformation};
+use crate::{
+    Angle, Clip, Fill, Filter, Mask, MaskMode, Point, Region, Stroke,
+    Transformation,
+};
 use itertools::Itertools;
 use std::fmt::Display;
 #[cfg(feature = "web")]
@@ -16,6 +19,10 @@ impl Shape {
         Object::from((self, None)).colored(color)
     }
 
+    pub fn stroked(self, stroke: Stroke) -> Object {
+        Object::from((self, None)).stroked(stroke)
+    }
+
     pub fn filtered(self, filter: Filter) -> Object {
         Object::from((self, None)).filtered(filter)
     }
@@ -25,14 +32,20 @@ impl Shape {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Hash)]
 pub struct Object {
     pub shape: Shape,
     pub fill: Option<Fill>,
+    /// Outline drawn in addition to the fill
+    pub stroke: Option<Stroke>,
     pub filters: Vec<Filter>,
     pub transformations: Vec<Transformation>,
     pub tags: Vec<String>,
-    pub clip_to: Option<Region>,
+    pub clip_to: Option<Clip>,
+    pub mask: Option<Mask>,
+    /// Objects with a higher z-index are drawn above the others of their layer. Ties are broken by object name.
+    /// Left to `None`, the layer stacks the object when it is added, see `Layer::set`.
+    pub z_index: Option<i32>,
 }
 
 impl Object {
@@ -56,6 +69,15 @@ impl Object {
         self
     }
 
+    pub fn stroked(mut self, stroke: Stroke) -> Self {
+        self.stroke = Some(stroke);
+        self
+    }
+
+    pub fn outlined(self, color: Color, width: f32) -> Self {
+        self.stroked(Stroke::solid(color, width))
+    }
+
     pub fn opacified(mut self, opacity: f32) -> Self {
         if let Some(fill) = &mut self.fill {
             *fill = fill.opacify(opacity);
@@ -63,8 +85,30 @@ impl Object {
         self
     }
 
-    pub fn clipped_to(mut self, region: impl Into<Region>) -> Self {
-        self.clip_to = Some(region.into());
+    /// Clips the object to a region or a shape
+    pub fn clipped_to(mut self, clip: impl Into<Clip>) -> Self {
+        self.clip_to = Some(clip.into());
+        self
+    }
+
+    /// Clips the object to the union of the objects of a layer
+    pub fn clipped_to_layer(self, layer: impl Into<String>) -> Self {
+        self.clipped_to(Clip::Layer(layer.into()))
+    }
+
+    pub fn masked(mut self, mask: Mask) -> Self {
+        self.mask = Some(mask);
+        self
+    }
+
+    /// Masks the object with the objects of a layer, see `Mask`
+    pub fn masked_by(self, layer: impl Into<String>, mode: MaskMode) -> Self {
+        self.masked(Mask::new(layer, mode))
+    }
+
+    /// Objects not stacked explicitly are stacked by their layer when added, see `Layer::set`
+    pub fn stacked_at(mut self, z_index: i32) -> Self {
+        self.z_index = Some(z_index);
         self
     }
 
@@ -80,6 +124,10 @@ impl Object {
         self.fill = Some(Fill::Solid(color))
     }
 
+    pub fn restroke(&mut self, stroke: Stroke) {
+        self.stroke = Some(stroke);
+    }
+
     pub fn filter(&mut self, filter: Filter) {
         self.filters.push(filter)
     }
@@ -132,18 +180,25 @@ impl std::fmt::Display for Object {
         let Object {
             shape: object,
             fill,
+            stroke,
             filters,
             transformations,
             tags,
             clip_to,
+            mask,
+            ..
         } = self;
 
         if fill.is_some() {
-            write!(f, "{:?} {:?}", fill.unwrap(), object)?;
+            write!(f, "{:?} {:?}", fill.as_ref().unwrap(), object)?;
         } else {
             write!(f, "transparent {:?}", object)?;
         }
 
+        if let Some(stroke) = stroke {
+            write!(f, " outlined with {:?}", stroke)?;
+        }
+
         if !filters.is_empty() {
             write!(f, " with filters {:?}", filters)?;
         }
@@ -160,6 +215,10 @@ impl std::fmt::Display for Object {
             write!(f, " (clipped to {:?})", clip_to)?;
         }
 
+        if let Some(mask) = mask {
+            write!(f, " (masked by {:?})", mask)?;
+        }
+
         Ok(())
     }
 }
@@ -169,10 +228,13 @@ impl From<Shape> for Object {
         Object {
             shape: value,
             fill: None,
+            stroke: None,
             filters: vec![],
             transformations: vec![],
             tags: vec![],
             clip_to: None,
+            mask: None,
+            z_index: None,
         }
     }
 }
@@ -182,10 +244,13 @@ impl From<(Shape, Option<Fill>)> for Object {
         Object {
             shape: object,
             fill,
+            stroke: None,
             filters: vec![],
             transformations: vec![],
             tags: vec![],
             clip_to: None,
+            mask: None,
+            z_index: None,
         }
     }
 }
@@ -0,0 +1,302 @@
+use std::path::PathBuf;
+
+use anyhow::{Result, anyhow};
+
+use crate::{Angle, Color, ColorMapping, Rgba};
+
+/// Number of hues in analogous palettes, and of colors other than the neutrals taken from images
+const HUES: usize = 9;
+
+/// Swatches of the hues of triadic palettes, starting from the seed's
+const TRIADIC_HUES: [&str; 3] = ["primary", "secondary", "tertiary"];
+
+/// Pixels of an image sampled to find its palette, at most
+const IMAGE_SAMPLES: usize = 10_000;
+
+impl ColorMapping {
+    /// Palette of hues next to the seed's, spread evenly up to `spread` on both sides.
+    /// The seed is the `primary` swatch, the other hues are `analogous-1` to `analogous-8`, alternating on each side and further away from the seed as they go.
+    /// The default colors keep their hue, but black, white and gray are tinted with the seed.
+    ///
+    /// ```
+    /// use shapemaker::{Angle, ColorMapping, Rgba};
+    ///
+    /// let palette = ColorMapping::analogous(Rgba::new(0xcf, 0x0a, 0x2b), Angle::from_degrees(30.0));
+    /// assert_eq!(palette.get("primary").unwrap(), "#cf0a2b");
+    /// assert!(palette.get("analogous-8").is_some());
+    /// assert_eq!(palette.get("blue").unwrap(), "blue");
+    /// ```
+    pub fn analogous(seed: Rgba, spread: Angle) -> ColorMapping {
+        let last = HUES - 1;
+        let hues = (0..=last).map(|i| {
+            let side = if i % 2 == 0 { -1.0 } else { 1.0 };
+            let offset = side * i.div_ceil(2) as f32 / last.div_ceil(2) as f32;
+            let name = match i {
+                0 => "primary".to_string(),
+                _ => format!("analogous-{i}"),
+            };
+            (
+                name,
+                seed.rotate_hue(Angle::from_degrees(offset * spread.degrees())),
+            )
+        });
+
+        generated(seed, hues)
+    }
+
+    /// Palette of three hues, evenly spaced around the color wheel starting from the seed's: `primary`, `secondary` and `tertiary`.
+    /// Each one also has a darker and a lighter shade, such as `primary-dark` and `primary-light`.
+    /// The default colors keep their hue, but black, white and gray are tinted with the seed.
+    pub fn triadic(seed: Rgba) -> ColorMapping {
+        let shades = TRIADIC_HUES.iter().enumerate().flat_map(|(i, name)| {
+            let hue = seed.rotate_hue(Angle::from_degrees(120.0 * i as f32));
+            [
+                (name.to_string(), hue),
+                (format!("{name}-dark"), hue.darken(0.3)),
+                (format!("{name}-light"), hue.lighten(0.3)),
+            ]
+        });
+
+        generated(seed, shades)
+    }
+
+    /// Palette of the most common colors of a PNG image, see `ColorMapping::from_pixmap`
+    pub fn from_image(path: PathBuf) -> Result<ColorMapping> {
+        let pixmap = tiny_skia::Pixmap::load_png(&path)
+            .map_err(|e| anyhow!("Can't read image {}: {e}", path.display()))?;
+        ColorMapping::from_pixmap(&pixmap)
+    }
+
+    /// Palette of the most common colors of the image, found by median cut.
+    /// The darkest and lightest colors become black and white, the least colorful one becomes gray,
+    /// and the other ones are the `image-1`, `image-2`… swatches, most common first.
+    /// Fails when the image has no opaque pixels.
+    pub fn from_pixmap(pixmap: &tiny_skia::Pixmap) -> Result<ColorMapping> {
+        let opaque: Vec<[u8; 3]> = pixmap
+            .pixels()
+            .iter()
+            .map(|pixel| pixel.demultiply())
+            .filter(|pixel| pixel.alpha() >= 128)
+            .map(|pixel| [pixel.red(), pixel.green(), pixel.blue()])
+            .collect();
+        if opaque.is_empty() {
+            return Err(anyhow!("The image has no opaque pixels"));
+        }
+
+        let step = opaque.len().div_ceil(IMAGE_SAMPLES);
+        let samples = opaque.into_iter().step_by(step).collect();
+        let mut colors = median_cut(samples, HUES + 3);
+        colors.sort_by_key(|&(_, count)| std::cmp::Reverse(count));
+
+        let mut mapping = ColorMapping::default();
+        for (color, key, lowest) in [
+            (Color::Black, Rgba::lightness as fn(&Rgba) -> f32, true),
+            (Color::White, Rgba::lightness, false),
+            (Color::Gray, Rgba::chroma, true),
+        ] {
+            let Some(index) = (0..colors.len()).min_by(|&a, &b| {
+                let order = key(&colors[a].0).total_cmp(&key(&colors[b].0));
+                if lowest { order } else { order.reverse() }
+            }) else {
+                break;
+            };
+            let (rgba, _) = colors.remove(index);
+            mapping.set(&color.name(), &rgba.to_string());
+        }
+
+        for (i, (rgba, _)) in colors.into_iter().enumerate() {
+            mapping.set(&format!("image-{}", i + 1), &rgba.to_string());
+        }
+        Ok(mapping)
+    }
+}
+
+/// Palette with the given swatches, and neutrals tinted with the seed
+fn generated(
+    seed: Rgba,
+    swatches: impl IntoIterator<Item = (String, Rgba)>,
+) -> ColorMapping {
+    let mut mapping = ColorMapping::default();
+
+    for (name, rgba) in swatches {
+        mapping.set(&name, &rgba.to_string());
+    }
+
+    for (color, neutral) in [
+        (Color::Black, Rgba::new(0, 0, 0)),
+        (Color::White, Rgba::new(255, 255, 255)),
+        (Color::Gray, Rgba::new(128, 128, 128)),
+    ] {
+        mapping.set(&color.name(), &seed.mix(neutral, 0.9).to_string());
+    }
+
+    mapping
+}
+
+/// Average colors of the pixels, split in at most `count` groups of similar colors, with the number of pixels of each group
+fn median_cut(pixels: Vec<[u8; 3]>, count: usize) -> Vec<(Rgba, usize)> {
+    // squared deviations from the mean, so that a few outliers don't make a group worth splitting
+    let spread = |group: &[[u8; 3]], channel: usize| {
+        let values = group.iter().map(|pixel| pixel[channel] as f32);
+        let mean = values.clone().sum::<f32>() / group.len() as f32;
+        values.map(|value| (value - mean).powi(2)).sum::<f32>()
+    };
+
+    let mut groups = vec![pixels];
+    while groups.len() < count {
+        // split the group with the most spread out values on a channel, at its median
+        let Some((index, channel, _)) = groups
+            .iter()
+            .enumerate()
+            .flat_map(|(index, group)| {
+                (0..3)
+                    .map(move |channel| (index, channel, spread(group, channel)))
+            })
+            .filter(|&(_, _, spread)| spread > 0.0)
+            .max_by(|(_, _, a), (_, _, b)| a.total_cmp(b))
+        else {
+            break;
+        };
+
+        let mut group = groups.swap_remove(index);
+        group.sort_unstable_by_key(|pixel| pixel[channel]);
+        let upper = group.split_off(group.len() / 2);
+        groups.push(group);
+        groups.push(upper);
+    }
+
+    groups
+        .into_iter()
+        .map(|group| {
+            let average = |channel: usize| {
+                let sum: usize =
+                    group.iter().map(|pixel| pixel[channel] as usize).sum();
+                (sum as f32 / group.len() as f32).round() as u8
+            };
+            (Rgba::new(average(0), average(1), average(2)), group.len())
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn resolved(mapping: &ColorMapping, color: impl Into<Color>) -> Rgba {
+        color.into().resolve(mapping).unwrap()
+    }
+
+    fn analogous_hues() -> Vec<String> {
+        (1..HUES).map(|i| format!("analogous-{i}")).collect()
+    }
+
+    /// Largest difference between the seed and the hues of an analogous palette
+    fn spread(mapping: &ColorMapping, seed: Rgba) -> f32 {
+        analogous_hues()
+            .iter()
+            .map(|name| resolved(mapping, name.as_str()).distance(seed))
+            .fold(0.0, f32::max)
+    }
+
+    fn pixmap(pixels: &[(Rgba, usize)]) -> tiny_skia::Pixmap {
+        let width = pixels.iter().map(|&(_, count)| count).sum::<usize>();
+        let mut pixmap = tiny_skia::Pixmap::new(width as u32, 1).unwrap();
+        let colors = pixels
+            .iter()
+            .flat_map(|&(rgba, count)| std::iter::repeat_n(rgba, count));
+        for (pixel, rgba) in pixmap.pixels_mut().iter_mut().zip(colors) {
+            *pixel = tiny_skia::ColorU8::from_rgba(
+                rgba.red, rgba.green, rgba.blue, rgba.alpha,
+            )
+            .premultiply();
+        }
+        pixmap
+    }
+
+    #[test]
+    fn analogous_palettes_stay_close_to_the_seed() {
+        let seed = Rgba::new(0xcf, 0x0a, 0x2b);
+        let narrow = ColorMapping::analogous(seed, Angle::from_degrees(30.0));
+        let wide = ColorMapping::analogous(seed, Angle::from_degrees(180.0));
+
+        assert_eq!(resolved(&narrow, "primary"), seed);
+        assert!(spread(&narrow, seed) < spread(&wide, seed));
+        for name in analogous_hues() {
+            let rgba = resolved(&narrow, name.as_str());
+            assert!((rgba.lightness() - seed.lightness()).abs() < 0.02);
+        }
+        // default colors keep their hue
+        assert_eq!(narrow.get("red").unwrap(), "red");
+    }
+
+    #[test]
+    fn triadic_palettes() {
+        let seed = Rgba::new(0x27, 0x34, 0xe6);
+        let palette = ColorMapping::triadic(seed);
+
+        assert_eq!(resolved(&palette, "primary"), seed);
+        assert_eq!(resolved(&palette, "primary-dark"), seed.darken(0.3));
+        assert_eq!(resolved(&palette, "primary-light"), seed.lighten(0.3));
+        assert_eq!(
+            resolved(&palette, "secondary"),
+            seed.rotate_hue(Angle::from_degrees(120.0))
+        );
+        assert_eq!(
+            resolved(&palette, "tertiary-light"),
+            seed.rotate_hue(Angle::from_degrees(240.0)).lighten(0.3)
+        );
+        assert_eq!(palette.get("blue").unwrap(), "blue");
+        assert_eq!(
+            resolved(&palette, Color::Black),
+            seed.mix(Rgba::new(0, 0, 0), 0.9)
+        );
+        assert!(resolved(&palette, Color::Gray).chroma() < seed.chroma());
+    }
+
+    #[test]
+    fn palettes_from_images() {
+        let (red, black, white, gray) = (
+            Rgba::new(255, 0, 0),
+            Rgba::new(0, 0, 0),
+            Rgba::new(255, 255, 255),
+            Rgba::new(128, 128, 128),
+        );
+        let palette = ColorMapping::from_pixmap(&pixmap(&[
+            (red, 8),
+            (black, 3),
+            (white, 2),
+            (gray, 1),
+            // transparent pixels are ignored
+            (Rgba::new(0, 0, 255).with_opacity(0.0), 20),
+        ]))
+        .unwrap();
+
+        assert_eq!(resolved(&palette, Color::Black), black);
+        assert_eq!(resolved(&palette, Color::White), white);
+        assert_eq!(resolved(&palette, Color::Gray), gray);
+        assert_eq!(resolved(&palette, "image-1"), red);
+        // default colors keep their hue
+        assert_eq!(palette.get("red").unwrap(), "red");
+
+        let transparent = pixmap(&[(red.with_opacity(0.0), 4)]);
+        assert!(ColorMapping::from_pixmap(&transparent).is_err());
+    }
+
+    #[test]
+    fn median_cut_groups_similar_colors() {
+        let pixels = [[250, 0, 0], [255, 0, 0], [0, 0, 250], [0, 0, 255]]
+            .into_iter()
+            .cycle()
+            .take(8)
+            .collect();
+        let mut groups = median_cut(pixels, 2);
+        groups.sort_by_key(|&(rgba, _)| rgba.red);
+        assert_eq!(
+            groups,
+            vec![(Rgba::new(0, 0, 253), 4), (Rgba::new(253, 0, 0), 4)]
+        );
+
+        // no more groups than distinct colors
+        assert_eq!(median_cut(vec![[1, 2, 3]; 5], 4).len(), 1);
+    }
+}
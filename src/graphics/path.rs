@@ -0,0 +1,483 @@
+use crate::{
+    CellSize, Point, Region, Shape,
+    geometry::hash_floats,
+    graphics::shapes::{
+        covering_points, quadratic_curve_extrema, quadratic_curve_point,
+    },
+};
+
+/// A point of the grid, optionally nudged by a fraction of a cell
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Anchor {
+    pub point: Point,
+    /// Offset from `point`, in cells
+    pub offset: (f32, f32),
+}
+
+impl Anchor {
+    pub fn new(point: impl Into<Point>, offset: (f32, f32)) -> Self {
+        Self {
+            point: point.into(),
+            offset,
+        }
+    }
+
+    /// Anchor at the given coordinates, in grid units
+    pub fn at_coords((x, y): (f32, f32)) -> Self {
+        Point::at(x, y).into()
+    }
+
+    pub fn nudged(self, dx: f32, dy: f32) -> Self {
+        Self {
+            offset: (self.offset.0 + dx, self.offset.1 + dy),
+            ..self
+        }
+    }
+
+    pub fn coords(&self, cell_size: &CellSize) -> (f32, f32) {
+        cell_size.coords(self.grid_coords())
+    }
+
+    /// Coordinates in cells, including the offset
+    pub fn grid_coords(&self) -> (f32, f32) {
+        let (x, y) = self.point.grid_coords();
+        (x + self.offset.0, y + self.offset.1)
+    }
+
+    pub fn translate(&mut self, dx: i32, dy: i32) {
+        self.point.translate(dx, dy)
+    }
+}
+
+impl std::hash::Hash for Anchor {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.point.hash(state);
+        hash_floats(&[self.offset.0, self.offset.1], state);
+    }
+}
+
+impl From<Point> for Anchor {
+    fn from(point: Point) -> Self {
+        Self::new(point, (0.0, 0.0))
+    }
+}
+
+impl From<(usize, usize)> for Anchor {
+    fn from(xy: (usize, usize)) -> Self {
+        Point::from(xy).into()
+    }
+}
+
+/// Command of a `Shape::Path`. Smooth curves reflect the previous curve's last control point, like in SVG.
+#[derive(Debug, Clone, PartialEq, Hash)]
+pub enum PathSegment {
+    MoveTo(Anchor),
+    LineTo(Anchor),
+    /// Cubic Bézier curve, with two control points
+    CurveTo(Anchor, Anchor, Anchor),
+    SmoothCurveTo(Anchor, Anchor),
+    QuadraticCurveTo(Anchor, Anchor),
+    SmoothQuadraticCurveTo(Anchor),
+    Close,
+}
+
+impl PathSegment {
+    pub fn anchors(&self) -> Vec<&Anchor> {
+        match self {
+            PathSegment::MoveTo(end)
+            | PathSegment::LineTo(end)
+            | PathSegment::SmoothQuadraticCurveTo(end) => vec![end],
+            PathSegment::CurveTo(control1, control2, end) => {
+                vec![control1, control2, end]
+            }
+            PathSegment::SmoothCurveTo(control, end)
+            | PathSegment::QuadraticCurveTo(control, end) => vec![control, end],
+            PathSegment::Close => vec![],
+        }
+    }
+
+    pub fn anchors_mut(&mut self) -> Vec<&mut Anchor> {
+        match self {
+            PathSegment::MoveTo(end)
+            | PathSegment::LineTo(end)
+            | PathSegment::SmoothQuadraticCurveTo(end) => vec![end],
+            PathSegment::CurveTo(control1, control2, end) => {
+                vec![control1, control2, end]
+            }
+            PathSegment::SmoothCurveTo(control, end)
+            | PathSegment::QuadraticCurveTo(control, end) => vec![control, end],
+            PathSegment::Close => vec![],
+        }
+    }
+}
+
+/// Builds a `Shape::Path`, see `Shape::path`
+#[derive(Debug, Clone, PartialEq)]
+pub struct PathBuilder(Vec<PathSegment>);
+
+impl PathBuilder {
+    pub fn new(start: impl Into<Anchor>) -> Self {
+        Self(vec![PathSegment::MoveTo(start.into())])
+    }
+
+    pub fn move_to(self, p: impl Into<Anchor>) -> Self {
+        self.with(PathSegment::MoveTo(p.into()))
+    }
+
+    pub fn line_to(self, p: impl Into<Anchor>) -> Self {
+        self.with(PathSegment::LineTo(p.into()))
+    }
+
+    pub fn curve_to(
+        self,
+        control1: impl Into<Anchor>,
+        control2: impl Into<Anchor>,
+        end: impl Into<Anchor>,
+    ) -> Self {
+        self.with(PathSegment::CurveTo(
+            control1.into(),
+            control2.into(),
+            end.into(),
+        ))
+    }
+
+    pub fn smooth_curve_to(
+        self,
+        control2: impl Into<Anchor>,
+        end: impl Into<Anchor>,
+    ) -> Self {
+        self.with(PathSegment::SmoothCurveTo(control2.into(), end.into()))
+    }
+
+    pub fn quadratic_curve_to(
+        self,
+        control: impl Into<Anchor>,
+        end: impl Into<Anchor>,
+    ) -> Self {
+        self.with(PathSegment::QuadraticCurveTo(control.into(), end.into()))
+    }
+
+    pub fn smooth_quadratic_curve_to(self, end: impl Into<Anchor>) -> Self {
+        self.with(PathSegment::SmoothQuadraticCurveTo(end.into()))
+    }
+
+    pub fn close(self) -> Self {
+        self.with(PathSegment::Close)
+    }
+
+    pub fn build(self) -> Shape {
+        Shape::Path(self.0)
+    }
+
+    fn with(mut self, segment: PathSegment) -> Self {
+        self.0.push(segment);
+        self
+    }
+}
+
+/// Closed path made of straight lines going through all the given anchors
+pub(crate) fn closed_polyline(
+    anchors: impl IntoIterator<Item = Anchor>,
+) -> Shape {
+    let mut anchors = anchors.into_iter();
+    let start = anchors.next().unwrap_or_default();
+    anchors
+        .fold(PathBuilder::new(start), |path, anchor| path.line_to(anchor))
+        .close()
+        .build()
+}
+
+impl From<PathBuilder> for Shape {
+    fn from(builder: PathBuilder) -> Self {
+        builder.build()
+    }
+}
+
+/// Segment of a path in grid units, with the implicit control points of smooth curves resolved.
+/// Closing a subpath becomes a line back to its start.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum ResolvedSegment {
+    Move((f32, f32)),
+    Line((f32, f32)),
+    Curve((f32, f32), (f32, f32), (f32, f32)),
+    QuadraticCurve((f32, f32), (f32, f32)),
+}
+
+impl ResolvedSegment {
+    pub fn end(&self) -> (f32, f32) {
+        match self {
+            ResolvedSegment::Move(end)
+            | ResolvedSegment::Line(end)
+            | ResolvedSegment::Curve(_, _, end)
+            | ResolvedSegment::QuadraticCurve(_, end) => *end,
+        }
+    }
+}
+
+pub(crate) fn resolve_path(segments: &[PathSegment]) -> Vec<ResolvedSegment> {
+    let coords = |anchor: &Anchor| anchor.grid_coords();
+    let reflect =
+        |(x, y): (f32, f32), (cx, cy): (f32, f32)| (2.0 * x - cx, 2.0 * y - cy);
+
+    let mut resolved: Vec<ResolvedSegment> = vec![];
+    let mut current = (0.0, 0.0);
+    let mut subpath_start = current;
+
+    for segment in segments {
+        // smooth curves reflect the last control point of the previous curve, if it is of the same kind
+        let smooth_control = |cubic: bool| match resolved.last() {
+            Some(ResolvedSegment::Curve(_, control, _)) if cubic => {
+                reflect(current, *control)
+            }
+            Some(ResolvedSegment::QuadraticCurve(control, _)) if !cubic => {
+                reflect(current, *control)
+            }
+            _ => current,
+        };
+
+        let segment = match segment {
+            PathSegment::MoveTo(end) => {
+                subpath_start = coords(end);
+                ResolvedSegment::Move(subpath_start)
+            }
+            PathSegment::LineTo(end) => ResolvedSegment::Line(coords(end)),
+            PathSegment::Close => ResolvedSegment::Line(subpath_start),
+            PathSegment::CurveTo(control1, control2, end) => {
+                ResolvedSegment::Curve(
+                    coords(control1),
+                    coords(control2),
+                    coords(end),
+                )
+            }
+            PathSegment::SmoothCurveTo(control2, end) => ResolvedSegment::Curve(
+                smooth_control(true),
+                coords(control2),
+                coords(end),
+            ),
+            PathSegment::QuadraticCurveTo(control, end) => {
+                ResolvedSegment::QuadraticCurve(coords(control), coords(end))
+            }
+            PathSegment::SmoothQuadraticCurveTo(end) => {
+                ResolvedSegment::QuadraticCurve(
+                    smooth_control(false),
+                    coords(end),
+                )
+            }
+        };
+
+        current = segment.end();
+        resolved.push(segment);
+    }
+
+    resolved
+}
+
+/// Smallest region containing the whole path, curves included
+pub(crate) fn path_region(segments: &[PathSegment]) -> Region {
+    let mut points: Vec<(f32, f32)> = vec![];
+    let mut current = (0.0, 0.0);
+
+    for segment in resolve_path(segments) {
+        match segment {
+            ResolvedSegment::Move(_) | ResolvedSegment::Line(_) => {}
+            ResolvedSegment::Curve(control1, control2, end) => points
+                .extend(cubic_curve_extrema(current, control1, control2, end)),
+            ResolvedSegment::QuadraticCurve(control, end) => {
+                points.extend(quadratic_curve_extrema(current, control, end))
+            }
+        }
+
+        current = segment.end();
+        points.push(current);
+    }
+
+    // empty paths are at the origin, see `Shape::position`
+    if points.is_empty() {
+        points.push(current);
+    }
+    covering_points(&points)
+}
+
+/// Polylines approximating each subpath of the path, in grid units
+pub(crate) fn flatten_path(
+    segments: &[PathSegment],
+    steps_per_curve: usize,
+) -> Vec<Vec<(f32, f32)>> {
+    let mut subpaths: Vec<Vec<(f32, f32)>> = vec![];
+    let mut current = (0.0, 0.0);
+    let steps = (1..=steps_per_curve).map(|i| i as f32 / steps_per_curve as f32);
+
+    for segment in resolve_path(segments) {
+        if subpaths.is_empty() || matches!(segment, ResolvedSegment::Move(_)) {
+            subpaths.push(vec![]);
+        }
+
+        let subpath = subpaths.last_mut().unwrap();
+        match segment {
+            ResolvedSegment::Move(end) | ResolvedSegment::Line(end) => {
+                subpath.push(end)
+            }
+            ResolvedSegment::Curve(control1, control2, end) => {
+                subpath.extend(steps.clone().map(|t| {
+                    cubic_curve_point(current, control1, control2, end, t)
+                }))
+            }
+            ResolvedSegment::QuadraticCurve(control, end) => subpath.extend(
+                steps
+                    .clone()
+                    .map(|t| quadratic_curve_point(current, control, end, t)),
+            ),
+        }
+
+        current = segment.end();
+    }
+
+    subpaths
+}
+
+/// Point at `t` (from 0 to 1) along a cubic Bézier curve
+pub(crate) fn cubic_curve_point(
+    start: (f32, f32),
+    control1: (f32, f32),
+    control2: (f32, f32),
+    end: (f32, f32),
+    t: f32,
+) -> (f32, f32) {
+    let u = 1.0 - t;
+    let coordinate = |p0: f32, p1: f32, p2: f32, p3: f32| {
+        u * u * u * p0
+            + 3.0 * u * u * t * p1
+            + 3.0 * u * t * t * p2
+            + t * t * t * p3
+    };
+    (
+        coordinate(start.0, control1.0, control2.0, end.0),
+        coordinate(start.1, control1.1, control2.1, end.1),
+    )
+}
+
+/// Points of a cubic Bézier curve where its x or y coordinate reaches an extremum, excluding the endpoints.
+fn cubic_curve_extrema(
+    start: (f32, f32),
+    control1: (f32, f32),
+    control2: (f32, f32),
+    end: (f32, f32),
+) -> Vec<(f32, f32)> {
+    let at = |t: f32| cubic_curve_point(start, control1, control2, end, t);
+
+    [
+        (start.0, control1.0, control2.0, end.0),
+        (start.1, control1.1, control2.1, end.1),
+    ]
+    .into_iter()
+    .flat_map(|(p0, p1, p2, p3)| {
+        // roots of the derivative, divided by 3: a t² + b t + c
+        let a = -p0 + 3.0 * p1 - 3.0 * p2 + p3;
+        let b = 2.0 * (p0 - 2.0 * p1 + p2);
+        let c = p1 - p0;
+
+        if a == 0.0 {
+            return if b == 0.0 { vec![] } else { vec![-c / b] };
+        }
+
+        let discriminant = b * b - 4.0 * a * c;
+        if discriminant < 0.0 {
+            return vec![];
+        }
+
+        let root = discriminant.sqrt();
+        vec![(-b + root) / (2.0 * a), (-b - root) / (2.0 * a)]
+    })
+    .filter(|t| (0.0..=1.0).contains(t))
+    .map(at)
+    .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cells(shape: &Shape) -> ((usize, usize), (usize, usize)) {
+        let region = shape.region();
+        (region.start.xy(), region.end.xy())
+    }
+
+    #[test]
+    fn smooth_curves_reflect_the_previous_control_point() {
+        let path = PathBuilder::new((0, 0))
+            .curve_to((0, 1), (1, 2), (2, 2))
+            .smooth_curve_to((4, 1), (4, 0))
+            .quadratic_curve_to((5, 1), (6, 0))
+            .smooth_quadratic_curve_to((8, 0))
+            .close();
+
+        assert_eq!(
+            resolve_path(&path.0)[1..],
+            [
+                ResolvedSegment::Curve((0.0, 1.0), (1.0, 2.0), (2.0, 2.0)),
+                ResolvedSegment::Curve((3.0, 2.0), (4.0, 1.0), (4.0, 0.0)),
+                ResolvedSegment::QuadraticCurve((5.0, 1.0), (6.0, 0.0)),
+                ResolvedSegment::QuadraticCurve((7.0, -1.0), (8.0, 0.0)),
+                ResolvedSegment::Line((0.0, 0.0)),
+            ]
+        );
+    }
+
+    #[test]
+    fn smooth_curves_after_other_segments_start_at_the_current_point() {
+        let path = PathBuilder::new((1, 1))
+            .line_to((2, 1))
+            .smooth_curve_to((3, 2), (3, 3));
+
+        assert_eq!(
+            resolve_path(&path.0)[2],
+            ResolvedSegment::Curve((2.0, 1.0), (3.0, 2.0), (3.0, 3.0))
+        );
+    }
+
+    #[test]
+    fn empty_paths_are_at_the_origin() {
+        let empty = Shape::Path(vec![]);
+        assert_eq!(empty.position(), Point::Corner(0, 0));
+        assert_eq!(cells(&empty), ((0, 0), (0, 0)));
+    }
+
+    #[test]
+    fn path_regions_include_curves() {
+        let straight = PathBuilder::new((0, 2)).line_to((2, 2)).build();
+        assert_eq!(cells(&straight), ((0, 2), (1, 2)));
+
+        // control points on the line don't make it bulge
+        let curved = PathBuilder::new((0, 2))
+            .curve_to((0, 2), (2, 2), (2, 2))
+            .build();
+        assert_eq!(cells(&curved), cells(&straight));
+
+        // bulges up to y = 0.5
+        let curved = PathBuilder::new((0, 2))
+            .curve_to((0, 0), (2, 0), (2, 2))
+            .build();
+        assert_eq!(cells(&curved), ((0, 0), (1, 1)));
+    }
+
+    #[test]
+    fn anchors_can_be_nudged_within_cells() {
+        let anchor = Anchor::from((1, 2)).nudged(0.25, 0.5);
+        assert_eq!(anchor.grid_coords(), (1.25, 2.5));
+        assert_eq!(anchor.coords(&CellSize::square(10)), (12.5, 25.0));
+    }
+
+    #[test]
+    fn flattened_subpaths() {
+        let path = PathBuilder::new((0, 0))
+            .quadratic_curve_to((1, 2), (2, 0))
+            .move_to((4, 0))
+            .line_to((5, 0));
+
+        let subpaths = flatten_path(&path.0, 4);
+        assert_eq!(subpaths.len(), 2);
+        assert_eq!(subpaths[0].len(), 5);
+        assert_eq!(subpaths[0][2], (1.0, 1.0));
+        assert_eq!(subpaths[1], vec![(4.0, 0.0), (5.0, 0.0)]);
+    }
+}
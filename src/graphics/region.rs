@@ -2,6 +2,9 @@ use crate::Region;
 
 impl Region {
     pub fn clip_path_id(&self) -> String {
-        format!("clip-{}-{}", self.start, self.end)
+        // Fractional regions can share cells while covering different areas
+        let ((start_x, start_y), (end_x, end_y)) =
+            (self.start.exact_xy(), self.end.exact_xy());
+        format!("clip-{start_x}-{start_y}-{end_x}-{end_y}")
     }
 }
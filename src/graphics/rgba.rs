@@ -0,0 +1,320 @@
+use std::str::FromStr;
+
+use anyhow::{Result, anyhow};
+
+use crate::Angle;
+
+/// Actual color, in sRGB, with its opacity. See `Color::resolve` to get the one of a palette's swatch.
+///
+/// Operations happen in the OKLab color space, so that the same amount of lightening or mixing
+/// looks the same whatever the hue. Colors that end up outside of sRGB lose some of their chroma, but keep their lightness and hue.
+///
+/// ```
+/// use shapemaker::Rgba;
+///
+/// let red: Rgba = "red".parse().unwrap();
+/// assert_eq!(red.to_string(), "#ff0000");
+/// assert_eq!(red.lighten(1.0).to_string(), "#ffffff");
+/// assert_eq!(red.mix(Rgba::new(0, 0, 255), 0.5).to_string(), "#8c53a2");
+/// assert_eq!(red.with_opacity(0.5).to_string(), "#ff000080");
+/// assert_eq!(Rgba::new(0, 0, 0).contrast_ratio(Rgba::new(255, 255, 255)).round(), 21.0);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Rgba {
+    pub red: u8,
+    pub green: u8,
+    pub blue: u8,
+    pub alpha: u8,
+}
+
+impl Rgba {
+    /// Opaque color
+    pub fn new(red: u8, green: u8, blue: u8) -> Self {
+        Self {
+            red,
+            green,
+            blue,
+            alpha: 255,
+        }
+    }
+
+    /// Opacity, from 0 to 1
+    pub fn opacity(&self) -> f32 {
+        self.alpha as f32 / 255.0
+    }
+
+    /// Same color, with the given opacity, from 0 to 1
+    pub fn with_opacity(self, opacity: f32) -> Self {
+        Self {
+            alpha: (opacity.clamp(0.0, 1.0) * 255.0).round() as u8,
+            ..self
+        }
+    }
+
+    /// Brings the OKLCH lightness closer to white, by `amount` (from 0 to 1) of the way
+    pub fn lighten(self, amount: f32) -> Self {
+        let (lightness, a, b) = self.oklab();
+        let lightness = lightness + (1.0 - lightness) * amount.clamp(0.0, 1.0);
+        Self::from_oklab((lightness, a, b), self.alpha)
+    }
+
+    /// Brings the OKLCH lightness closer to black, by `amount` (from 0 to 1) of the way
+    pub fn darken(self, amount: f32) -> Self {
+        let (lightness, a, b) = self.oklab();
+        let lightness = lightness * (1.0 - amount.clamp(0.0, 1.0));
+        Self::from_oklab((lightness, a, b), self.alpha)
+    }
+
+    /// Color `ratio` (from 0 to 1) of the way from this one to `other`, in OKLab. Opacities are mixed too.
+    pub fn mix(self, other: Rgba, ratio: f32) -> Self {
+        let ratio = ratio.clamp(0.0, 1.0);
+        let between = |from: f32, to: f32| from + (to - from) * ratio;
+
+        let ((l1, a1, b1), (l2, a2, b2)) = (self.oklab(), other.oklab());
+        Self::from_oklab(
+            (between(l1, l2), between(a1, a2), between(b1, b2)),
+            between(self.alpha as f32, other.alpha as f32).round() as u8,
+        )
+    }
+
+    /// Same lightness and chroma, with the OKLCH hue turned by the angle
+    pub fn rotate_hue(self, angle: Angle) -> Self {
+        let (lightness, a, b) = self.oklab();
+        let (cos, sin) = angle.cos_sin();
+        Self::from_oklab(
+            (lightness, a * cos - b * sin, a * sin + b * cos),
+            self.alpha,
+        )
+    }
+
+    /// OKLCH lightness, from 0 (black) to 1 (white)
+    pub fn lightness(&self) -> f32 {
+        self.oklab().0
+    }
+
+    /// OKLCH chroma: 0 for grays, up to about 0.32 for the most vivid colors of sRGB
+    pub fn chroma(&self) -> f32 {
+        let (_, a, b) = self.oklab();
+        a.hypot(b)
+    }
+
+    /// Perceptual difference between the colors, as their distance in OKLab. Opacity is not taken into account.
+    pub fn distance(&self, other: Rgba) -> f32 {
+        let ((l1, a1, b1), (l2, a2, b2)) = (self.oklab(), other.oklab());
+        ((l1 - l2).powi(2) + (a1 - a2).powi(2) + (b1 - b2).powi(2)).sqrt()
+    }
+
+    /// WCAG contrast ratio between the colors, from 1 (same luminance) to 21 (black on white). Opacity is not taken into account.
+    pub fn contrast_ratio(&self, other: Rgba) -> f32 {
+        let (lighter, darker) = (
+            self.luminance().max(other.luminance()),
+            self.luminance().min(other.luminance()),
+        );
+        (lighter + 0.05) / (darker + 0.05)
+    }
+
+    /// Relative luminance, as defined by WCAG
+    fn luminance(&self) -> f32 {
+        let [r, g, b] = [self.red, self.green, self.blue]
+            .map(|c| to_linear(c as f32 / 255.0));
+        0.2126 * r + 0.7152 * g + 0.0722 * b
+    }
+
+    /// Lightness, a and b coordinates in OKLab
+    fn oklab(&self) -> (f32, f32, f32) {
+        let [r, g, b] = [self.red, self.green, self.blue]
+            .map(|c| to_linear(c as f32 / 255.0));
+
+        let l = (0.41222147 * r + 0.53633254 * g + 0.051445995 * b).cbrt();
+        let m = (0.2119035 * r + 0.6806995 * g + 0.10739696 * b).cbrt();
+        let s = (0.08830246 * r + 0.28171884 * g + 0.6299787 * b).cbrt();
+
+        (
+            0.21045426 * l + 0.7936178 * m - 0.004072047 * s,
+            1.9779985 * l - 2.4285922 * m + 0.4505937 * s,
+            0.025904037 * l + 0.78277177 * m - 0.80867577 * s,
+        )
+    }
+
+    /// Color with the given OKLab coordinates. Out of gamut colors have their chroma reduced until they fit in sRGB.
+    fn from_oklab((lightness, a, b): (f32, f32, f32), alpha: u8) -> Self {
+        let mut rgb = linear_rgb((lightness, a, b));
+
+        if !in_gamut(rgb) {
+            // bisect the part of the chroma (the distance to the gray axis) to keep
+            let (mut kept, mut removed) = (0.0, 1.0);
+            for _ in 0..16 {
+                let ratio = (kept + removed) / 2.0;
+                if in_gamut(linear_rgb((lightness, a * ratio, b * ratio))) {
+                    kept = ratio;
+                } else {
+                    removed = ratio;
+                }
+            }
+            rgb = linear_rgb((lightness, a * kept, b * kept));
+        }
+
+        let [red, green, blue] =
+            rgb.map(|c| (from_linear(c.clamp(0.0, 1.0)) * 255.0).round() as u8);
+        Self {
+            red,
+            green,
+            blue,
+            alpha,
+        }
+    }
+}
+
+impl std::fmt::Display for Rgba {
+    /// Hexadecimal notation, with the opacity only when the color is not opaque
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "#{:02x}{:02x}{:02x}", self.red, self.green, self.blue)?;
+        if self.alpha < 255 {
+            write!(f, "{:02x}", self.alpha)?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Rgba {
+    type Err = anyhow::Error;
+
+    /// Parses any CSS color: hexadecimal notations, `rgb()`, `hsl()`, or a color name
+    fn from_str(s: &str) -> Result<Self> {
+        let color = svgtypes::Color::from_str(s.trim())
+            .map_err(|e| anyhow!("{s:?} is not a valid CSS color: {e}"))?;
+
+        Ok(Self {
+            red: color.red,
+            green: color.green,
+            blue: color.blue,
+            alpha: color.alpha,
+        })
+    }
+}
+
+/// Linear sRGB channels of the OKLab coordinates, possibly outside of 0 to 1
+fn linear_rgb((lightness, a, b): (f32, f32, f32)) -> [f32; 3] {
+    let l = (lightness + 0.39633778 * a + 0.21580376 * b).powi(3);
+    let m = (lightness - 0.105561346 * a - 0.06385417 * b).powi(3);
+    let s = (lightness - 0.08948418 * a - 1.2914855 * b).powi(3);
+
+    [
+        4.0767417 * l - 3.3077116 * m + 0.23096993 * s,
+        -1.268438 * l + 2.6097574 * m - 0.34131938 * s,
+        -0.0041960863 * l - 0.7034186 * m + 1.7076147 * s,
+    ]
+}
+
+fn in_gamut(rgb: [f32; 3]) -> bool {
+    rgb.iter().all(|c| (-1e-4..=1.0 + 1e-4).contains(c))
+}
+
+fn to_linear(channel: f32) -> f32 {
+    if channel <= 0.04045 {
+        channel / 12.92
+    } else {
+        ((channel + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+fn from_linear(channel: f32) -> f32 {
+    if channel <= 0.0031308 {
+        channel * 12.92
+    } else {
+        1.055 * channel.powf(1.0 / 2.4) - 0.055
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hue(color: Rgba) -> f32 {
+        let (_, a, b) = color.oklab();
+        b.atan2(a).to_degrees().rem_euclid(360.0)
+    }
+
+    fn hex(s: &str) -> Rgba {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn oklab_round_trips() {
+        for color in [
+            "#000000", "#ffffff", "#ff0000", "#00ff00", "#0000ff", "#8c53a2",
+        ] {
+            let rgba = hex(color);
+            assert_eq!(Rgba::from_oklab(rgba.oklab(), 255), rgba);
+        }
+        assert!(hex("#808080").chroma() < 1e-3);
+    }
+
+    #[test]
+    fn lightening_and_darkening() {
+        let red = hex("#ff0000");
+        assert_eq!(red.lighten(0.0), red);
+        assert_eq!(red.darken(1.0).to_string(), "#000000");
+        assert!(red.lighten(0.3).lightness() > red.lightness());
+        assert!(red.darken(0.3).lightness() < red.lightness());
+        // amounts past 1 don't go further than white
+        assert_eq!(red.lighten(2.0), red.lighten(1.0));
+        assert_eq!(red.with_opacity(0.5).darken(0.5).alpha, 128);
+    }
+
+    #[test]
+    fn out_of_gamut_colors_lose_chroma_only() {
+        let blue = hex("#0000ff");
+        let target = blue.lightness() + (1.0 - blue.lightness()) * 0.5;
+        let lighter = blue.lighten(0.5);
+
+        // keeping blue's chroma that light would leave sRGB
+        assert!(!in_gamut(linear_rgb((
+            target,
+            blue.oklab().1,
+            blue.oklab().2
+        ))));
+        assert!((lighter.lightness() - target).abs() < 0.01);
+        assert!((hue(lighter) - hue(blue)).abs() < 2.0);
+        assert!(lighter.chroma() < blue.chroma());
+    }
+
+    #[test]
+    fn rotating_hues() {
+        let red = hex("#ff0000");
+        let rotated = red.rotate_hue(Angle(120.0));
+        assert!((rotated.lightness() - red.lightness()).abs() < 0.01);
+        assert!((hue(rotated) - (hue(red) + 120.0)).abs() < 2.0);
+        assert!(rotated.chroma() <= red.chroma() + 1e-3);
+
+        let gray = hex("#808080");
+        assert_eq!(gray.rotate_hue(Angle(90.0)), gray);
+    }
+
+    #[test]
+    fn mixing() {
+        let (red, blue) = (hex("#ff0000"), hex("#0000ff"));
+        assert_eq!(red.mix(blue, 0.0), red);
+        assert_eq!(red.mix(blue, 1.0), blue);
+        assert_eq!(red.mix(blue, 0.5), blue.mix(red, 0.5));
+        assert_eq!(red.mix(blue.with_opacity(0.0), 0.5).alpha, 128);
+    }
+
+    #[test]
+    fn parsing_css_colors() {
+        assert_eq!(hex("rgb(255 128 0)"), Rgba::new(255, 128, 0));
+        assert_eq!(hex("hsl(0, 100%, 50%)"), Rgba::new(255, 0, 0));
+        assert_eq!(hex(" teal "), Rgba::new(0, 128, 128));
+        assert_eq!(hex("#ff800080").to_string(), "#ff800080");
+        assert!("banana".parse::<Rgba>().is_err());
+    }
+
+    #[test]
+    fn contrast_ratios() {
+        let (black, white) = (hex("#000000"), hex("#ffffff"));
+        assert_eq!(black.contrast_ratio(black), 1.0);
+        assert_eq!(black.contrast_ratio(white), white.contrast_ratio(black));
+        assert!(hex("#767676").contrast_ratio(white) >= 4.5);
+        assert!(black.distance(white) > 0.99);
+    }
+}
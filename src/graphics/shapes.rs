@@ -1,17 +1,294 @@
 use self::Shape::*;
-use crate::{Containable, Object, Point, Region};
-use anyhow::anyhow;
+use super::path::{
+    Anchor, PathBuilder, PathSegment, closed_polyline, path_region,
+};
+use crate::rendering::rasterization::svg_bounding_box;
+use crate::{Angle, Containable, Object, Point, Region, geometry::hash_floats};
+use anyhow::{Result, anyhow};
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Hash)]
 pub enum LineSegment {
     Straight(Point),
     InwardCurve(Point),
     OutwardCurve(Point),
 }
 
+impl LineSegment {
+    pub fn end(&self) -> Point {
+        match self {
+            LineSegment::Straight(end)
+            | LineSegment::InwardCurve(end)
+            | LineSegment::OutwardCurve(end) => *end,
+        }
+    }
+
+    /// Control point of the quadratic Bézier curve drawn from `start` to the end of this segment, in grid units.
+    /// Follows the same direction convention as `Shape::CurveInward` and `Shape::CurveOutward`.
+    /// Straight segments have no control point.
+    pub fn control_point(&self, start: Point) -> Option<(f32, f32)> {
+        let inward = match self {
+            LineSegment::Straight(_) => return None,
+            LineSegment::InwardCurve(_) => true,
+            LineSegment::OutwardCurve(_) => false,
+        };
+
+        Some(curve_control_point(
+            start.grid_coords(),
+            self.end().grid_coords(),
+            inward,
+        ))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum HorizontalAlignment {
+    #[default]
+    Left,
+    Center,
+    Right,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum VerticalAlignment {
+    #[default]
+    Top,
+    Middle,
+    Bottom,
+}
+
+/// How the two shapes of a `Shape::Boolean` are combined
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BooleanOperation {
+    /// Area covered by either shape
+    Union,
+    /// Area covered by both shapes
+    Intersection,
+    /// Area covered by the first shape but not the second one
+    Difference,
+    /// Area covered by exactly one of the shapes
+    Xor,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct TilingOptions {
+    /// Distance between two consecutive tiles, in cells. Defaults to the tile's dimensions.
+    pub step: Option<(f32, f32)>,
+    /// Offset of the first tile from the region's top-left corner, in cells
+    pub offset: (f32, f32),
+    /// Horizontal shift applied to every other row, in cells
+    pub stagger: f32,
+    /// Whether to mirror every other column horizontally, and every other row vertically
+    pub mirror: (bool, bool),
+}
+
+impl TilingOptions {
+    pub fn step(self, x: f32, y: f32) -> Self {
+        Self {
+            step: Some((x, y)),
+            ..self
+        }
+    }
+
+    pub fn offset(self, x: f32, y: f32) -> Self {
+        Self {
+            offset: (x, y),
+            ..self
+        }
+    }
+
+    pub fn stagger(self, stagger: f32) -> Self {
+        Self { stagger, ..self }
+    }
+
+    pub fn mirror(self, horizontally: bool, vertically: bool) -> Self {
+        Self {
+            mirror: (horizontally, vertically),
+            ..self
+        }
+    }
+}
+
+impl std::hash::Hash for TilingOptions {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        let TilingOptions {
+            step,
+            offset,
+            stagger,
+            mirror,
+        } = *self;
+        step.is_some().hash(state);
+        let (step_x, step_y) = step.unwrap_or_default();
+        hash_floats(&[step_x, step_y, offset.0, offset.1, stagger], state);
+        mirror.hash(state);
+    }
+}
+
+/// Computes the control point of the quadratic Bézier curve used to draw inward and outward curves.
+/// Works with any unit, as long as `start` and `end` use the same one.
+pub(crate) fn curve_control_point(
+    (start_x, start_y): (f32, f32),
+    (end_x, end_y): (f32, f32),
+    inward: bool,
+) -> (f32, f32) {
+    // zero-length curves, such as between repeated vertices, have nowhere to bulge to
+    if (start_x, start_y) == (end_x, end_y) {
+        return (start_x, start_y);
+    }
+
+    let midpoint = ((start_x + end_x) / 2.0, (start_y + end_y) / 2.0);
+    let start_from_midpoint = (start_x - midpoint.0, start_y - midpoint.1);
+    let end_from_midpoint = (end_x - midpoint.0, end_y - midpoint.1);
+    let relative = (end_x - start_x, end_y - start_y);
+
+    // diagonal line is going like this: \
+    if start_from_midpoint.0 * start_from_midpoint.1 > 0.0
+        && end_from_midpoint.0 * end_from_midpoint.1 > 0.0
+    {
+        if inward {
+            (
+                midpoint.0 + relative.0.abs() / 2.0,
+                midpoint.1 - relative.1.abs() / 2.0,
+            )
+        } else {
+            (
+                midpoint.0 - relative.0.abs() / 2.0,
+                midpoint.1 + relative.1.abs() / 2.0,
+            )
+        }
+    // diagonal line is going like this: /
+    } else if start_from_midpoint.0 * start_from_midpoint.1 < 0.0
+        && end_from_midpoint.0 * end_from_midpoint.1 < 0.0
+    {
+        if inward {
+            (
+                midpoint.0 - relative.0.abs() / 2.0,
+                midpoint.1 - relative.1.abs() / 2.0,
+            )
+        } else {
+            (
+                midpoint.0 + relative.0.abs() / 2.0,
+                midpoint.1 + relative.1.abs() / 2.0,
+            )
+        }
+    // line is horizontal
+    } else if start_y == end_y {
+        (
+            midpoint.0,
+            midpoint.1
+                + (if inward { -1.0 } else { 1.0 }) * relative.0.abs() / 2.0,
+        )
+    // line is vertical
+    } else if start_x == end_x {
+        (
+            midpoint.0
+                + (if inward { -1.0 } else { 1.0 }) * relative.1.abs() / 2.0,
+            midpoint.1,
+        )
+    // only reachable with non-finite coordinates
+    } else {
+        midpoint
+    }
+}
+
+/// Smallest region containing all the given points, in grid units
+pub(crate) fn covering_points(points: &[(f32, f32)]) -> Region {
+    let (min, max) = points.iter().fold(
+        (
+            (f32::INFINITY, f32::INFINITY),
+            (f32::NEG_INFINITY, f32::NEG_INFINITY),
+        ),
+        |(min, max), &(x, y)| {
+            ((min.0.min(x), min.1.min(y)), (max.0.max(x), max.1.max(y)))
+        },
+    );
+    Region::covering(min, max)
+}
+
+/// Point at `t` (from 0 to 1) along a quadratic Bézier curve
+pub(crate) fn quadratic_curve_point(
+    start: (f32, f32),
+    control: (f32, f32),
+    end: (f32, f32),
+    t: f32,
+) -> (f32, f32) {
+    let u = 1.0 - t;
+    (
+        u * u * start.0 + 2.0 * u * t * control.0 + t * t * end.0,
+        u * u * start.1 + 2.0 * u * t * control.1 + t * t * end.1,
+    )
+}
+
+/// Points of a quadratic Bézier curve where its x or y coordinate reaches an extremum, excluding the endpoints.
+pub(crate) fn quadratic_curve_extrema(
+    start: (f32, f32),
+    control: (f32, f32),
+    end: (f32, f32),
+) -> Vec<(f32, f32)> {
+    let at = |t: f32| quadratic_curve_point(start, control, end, t);
+
+    [(start.0, control.0, end.0), (start.1, control.1, end.1)]
+        .into_iter()
+        .filter_map(|(p0, p1, p2)| {
+            let denominator = p0 - 2.0 * p1 + p2;
+            if denominator == 0.0 {
+                return None;
+            }
+            let t = (p0 - p1) / denominator;
+            (0.0..=1.0).contains(&t).then(|| at(t))
+        })
+        .collect()
+}
+
+/// Point at `angle` on a circle. Angles go clockwise from the positive x axis, since the y axis points down.
+/// Works with any unit, as long as `center` and `radius` use the same one.
+pub(crate) fn point_on_circle(
+    (center_x, center_y): (f32, f32),
+    radius: f32,
+    angle: Angle,
+) -> (f32, f32) {
+    let (cos, sin) = angle.cos_sin();
+    // cos and sin of quarter turns are not exact, which would spill regions over to the next cell
+    let snap = |value: f32| (value * 1e4).round() / 1e4;
+    (center_x + snap(radius * cos), center_y + snap(radius * sin))
+}
+
+/// Closed path going through vertices given as (distance, angle) from `center`
+fn closed_path_around(
+    center: Point,
+    vertices: impl IntoIterator<Item = (f32, Angle)>,
+) -> Shape {
+    closed_polyline(vertices.into_iter().map(|(distance, angle)| {
+        Anchor::new(center, point_on_circle((0.0, 0.0), distance, angle))
+    }))
+}
+
+/// Points of a circular arc where its x or y coordinate reaches an extremum, including the endpoints.
+fn arc_extrema(
+    center: (f32, f32),
+    radius: f32,
+    start: Angle,
+    end: Angle,
+) -> Vec<(f32, f32)> {
+    let (from, to) = (
+        start.degrees().min(end.degrees()),
+        start.degrees().max(end.degrees()),
+    );
+
+    let quarter_turns = ((from / 90.0).ceil() as i32)
+        ..=((to.min(from + 360.0) / 90.0).floor() as i32);
+
+    quarter_turns
+        .map(|quarter| Angle::from_degrees(quarter as f32 * 90.0))
+        .chain([start, end])
+        .map(|angle| point_on_circle(center, radius, angle))
+        .collect()
+}
+
 #[derive(Debug, Clone)]
 pub enum Shape {
     Polygon(Point, Vec<LineSegment>),
+    /// Free-form path, see `Shape::path` to build one. Should start with a `PathSegment::MoveTo`.
+    Path(Vec<PathSegment>),
     Line(Point, Point, f32),
     CurveOutward(Point, Point, f32),
     CurveInward(Point, Point, f32),
@@ -21,15 +298,52 @@ pub enum Shape {
     BigCircle(Point),
     Text(Point, String, f32),
     CenteredText(Point, String, f32),
-    // FittedText(Region, String),
+    /// Text that wraps and picks the largest font size that fits in the region
+    FittedText {
+        region: Region,
+        content: String,
+        horizontal: HorizontalAlignment,
+        vertical: VerticalAlignment,
+    },
     Rectangle(Point, Point),
+    /// Ellipse inscribed in the region
+    Ellipse(Region),
+    /// Circular arc, drawn like a line.
+    /// Angles go clockwise, starting from the positive x axis (3 o'clock). The arc goes counter-clockwise if `end` is before `start`.
+    Arc {
+        center: Point,
+        radius_cells: f32,
+        start: Angle,
+        end: Angle,
+    },
+    /// Pie slice, or donut slice if `inner_radius_cells` is not zero. Angles work like for `Arc`.
+    Sector {
+        center: Point,
+        inner_radius_cells: f32,
+        outer_radius_cells: f32,
+        start: Angle,
+        end: Angle,
+    },
     Image(Region, String),
+    /// Arbitrary SVG content. Coordinates in the content are in grid units, relative to `at`.
+    /// Use `Shape::raw_svg` to construct it, since it computes `bounds` for you.
     RawSVG {
         content: String,
         /// String to search & replace in the SVG content when applying a color fill to the shape (see `Object`). For example, `#c0ffee`.
         color: String,
+        /// Origin of the content's coordinate system
+        at: Point,
+        /// Bounding box of the content, as (min, max) corners, in grid units relative to `at`
+        bounds: ((f32, f32), (f32, f32)),
     },
-    // Tiling(Region, Box<Object>),
+    /// Combination of two shapes. Outlines are not drawn, since the result is painted through a mask.
+    Boolean {
+        op: BooleanOperation,
+        a: Box<Shape>,
+        b: Box<Shape>,
+    },
+    /// Repeats an object across the region. The object is rendered once and reused through a SVG pattern.
+    Tiling(Region, Box<Object>, TilingOptions),
     Component {
         at: Point,
         size: (usize, usize),
@@ -37,8 +351,182 @@ pub enum Shape {
     },
 }
 
+impl std::hash::Hash for Shape {
+    /// Hashes every field, so that shapes drawn the same hash the same
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        std::mem::discriminant(self).hash(state);
+        match self {
+            Polygon(start, lines) => (start, lines).hash(state),
+            Path(segments) => segments.hash(state),
+            Line(start, end, width)
+            | CurveOutward(start, end, width)
+            | CurveInward(start, end, width) => {
+                (start, end).hash(state);
+                hash_floats(&[*width], state);
+            }
+            SmallCircle(at) | BigDot(at) | Dot(at) | BigCircle(at) => {
+                at.hash(state)
+            }
+            Text(at, content, size) | CenteredText(at, content, size) => {
+                (at, content).hash(state);
+                hash_floats(&[*size], state);
+            }
+            FittedText {
+                region,
+                content,
+                horizontal,
+                vertical,
+            } => (region, content, horizontal, vertical).hash(state),
+            Rectangle(start, end) => (start, end).hash(state),
+            Ellipse(region) => region.hash(state),
+            Arc {
+                center,
+                radius_cells,
+                start,
+                end,
+            } => {
+                (center, start, end).hash(state);
+                hash_floats(&[*radius_cells], state);
+            }
+            Sector {
+                center,
+                inner_radius_cells,
+                outer_radius_cells,
+                start,
+                end,
+            } => {
+                (center, start, end).hash(state);
+                hash_floats(&[*inner_radius_cells, *outer_radius_cells], state);
+            }
+            Image(region, path) => (region, path).hash(state),
+            RawSVG {
+                content,
+                color,
+                at,
+                bounds: ((min_x, min_y), (max_x, max_y)),
+            } => {
+                (content, color, at).hash(state);
+                hash_floats(&[*min_x, *min_y, *max_x, *max_y], state);
+            }
+            Boolean { op, a, b } => (op, a, b).hash(state),
+            Tiling(region, object, options) => {
+                (region, object, options).hash(state)
+            }
+            Component { at, size, objects } => (at, size, objects).hash(state),
+        }
+    }
+}
+
 impl Shape {
-    pub fn translate(&mut self, dx: i32, dy: i32) {
+    /// Starts building a `Path` shape at `start`
+    /// ```
+    /// use shapemaker::{Anchor, Point::Corner, Shape};
+    ///
+    /// let wave = Shape::path(Corner(0, 2))
+    ///     .curve_to(Corner(1, 0), Anchor::new(Corner(1, 4), (0.5, 0.0)), Corner(3, 2))
+    ///     .build();
+    /// assert_eq!(wave.region().end, (2, 2));
+    /// ```
+    pub fn path(start: impl Into<Anchor>) -> PathBuilder {
+        PathBuilder::new(start)
+    }
+
+    /// Regular polygon inscribed in a circle of `radius` cells around `center`.
+    /// The first vertex is at `rotation`, with angles working like for `Arc`.
+    pub fn regular_polygon(
+        center: impl Into<Point>,
+        radius: f32,
+        sides: usize,
+        rotation: Angle,
+    ) -> Shape {
+        let step = Angle::TURN.degrees() / sides as f32;
+        closed_path_around(
+            center.into(),
+            (0..sides).map(|i| {
+                (
+                    radius,
+                    Angle::from_degrees(rotation.degrees() + i as f32 * step),
+                )
+            }),
+        )
+    }
+
+    /// Star whose tips are `outer` cells away from `center`, and inner vertices `inner` cells away.
+    /// The first tip points up.
+    pub fn star(
+        center: impl Into<Point>,
+        outer: f32,
+        inner: f32,
+        points: usize,
+    ) -> Shape {
+        let step = Angle::TURN.degrees() / (2 * points) as f32;
+        closed_path_around(
+            center.into(),
+            (0..2 * points).map(|i| {
+                (
+                    if i % 2 == 0 { outer } else { inner },
+                    Angle::from_degrees(-90.0 + i as f32 * step),
+                )
+            }),
+        )
+    }
+
+    pub fn combined(self, op: BooleanOperation, other: Shape) -> Shape {
+        Boolean {
+            op,
+            a: Box::new(self),
+            b: Box::new(other),
+        }
+    }
+
+    pub fn union(self, other: Shape) -> Shape {
+        self.combined(BooleanOperation::Union, other)
+    }
+
+    pub fn intersection(self, other: Shape) -> Shape {
+        self.combined(BooleanOperation::Intersection, other)
+    }
+
+    /// This shape, with `other` cut out of it
+    pub fn difference(self, other: Shape) -> Shape {
+        self.combined(BooleanOperation::Difference, other)
+    }
+
+    pub fn xor(self, other: Shape) -> Shape {
+        self.combined(BooleanOperation::Xor, other)
+    }
+
+    /// Creates a `RawSVG` shape, positioned at the origin. Its content is parsed to compute its bounding box.
+    /// `content` can be a whole `<svg>` document or a fragment, such as a single `<path>`.
+    pub fn raw_svg(
+        content: impl Into<String>,
+        color: impl Into<String>,
+    ) -> Result<Shape> {
+        let content = content.into();
+        let bounds = svg_bounding_box(&content).map_err(|e| {
+            anyhow!("Could not compute bounding box of raw SVG: {e:?}")
+        })?;
+
+        Ok(RawSVG {
+            content,
+            color: color.into(),
+            at: Point::Corner(0, 0),
+            bounds,
+        })
+    }
+
+    /// Moves the shape by `dx` and `dy` cells.
+    /// Whole numbers keep the shape on the grid, fractions make it glide between cells (see `Shape::nudge`).
+    pub fn translate(&mut self, dx: impl Into<f64>, dy: impl Into<f64>) {
+        let (dx, dy) = (dx.into(), dy.into());
+        if dx.fract() == 0.0 && dy.fract() == 0.0 {
+            self.translate_cells(dx as i32, dy as i32);
+        } else {
+            self.nudge(dx as f32, dy as f32);
+        }
+    }
+
+    fn translate_cells(&mut self, dx: i32, dy: i32) {
         match self {
             Polygon(start, lines) => {
                 start.translate(dx, dy);
@@ -52,6 +540,10 @@ impl Shape {
                     }
                 }
             }
+            Path(segments) => segments
+                .iter_mut()
+                .flat_map(|segment| segment.anchors_mut())
+                .for_each(|anchor| anchor.translate(dx, dy)),
             Line(start, end, _)
             | CurveInward(start, end, _)
             | CurveOutward(start, end, _)
@@ -59,21 +551,85 @@ impl Shape {
                 start.translate(dx, dy);
                 end.translate(dx, dy);
             }
-            Text(anchor, _, _)
-            | CenteredText(anchor, ..)
-            | Dot(anchor)
-            | BigDot(anchor) => anchor.translate(dx, dy),
-            BigCircle(center) | SmallCircle(center) => center.translate(dx, dy),
-            Image(region, ..) => region.translate(dx, dy),
-            Component { at, .. } => at.translate(dx, dy),
-            RawSVG { .. } => {
-                unimplemented!()
+            Text(..) | CenteredText(..) | BigCircle(..) | SmallCircle(..) => {
+                self.nudge(dx as f32, dy as f32)
+            }
+            Dot(anchor) | BigDot(anchor) => anchor.translate(dx, dy),
+            Arc { center, .. } | Sector { center, .. } => {
+                center.translate(dx, dy)
+            }
+            Image(region, ..)
+            | Ellipse(region)
+            | FittedText { region, .. }
+            | Tiling(region, ..) => region.translate(dx, dy),
+            Component { at, .. } | RawSVG { at, .. } => at.translate(dx, dy),
+            Boolean { a, b, .. } => {
+                a.translate_cells(dx, dy);
+                b.translate_cells(dx, dy);
+            }
+        }
+    }
+
+    /// Moves the shape by a fraction of a cell, turning its points into fractional ones where needed (see `Point::nudged`)
+    pub fn nudge(&mut self, dx: f32, dy: f32) {
+        match self {
+            Polygon(start, lines) => {
+                start.nudge(dx, dy);
+                for line in lines {
+                    match line {
+                        LineSegment::InwardCurve(anchor)
+                        | LineSegment::OutwardCurve(anchor)
+                        | LineSegment::Straight(anchor) => anchor.nudge(dx, dy),
+                    }
+                }
+            }
+            Path(segments) => segments
+                .iter_mut()
+                .flat_map(|segment| segment.anchors_mut())
+                .for_each(|anchor| *anchor = anchor.nudged(dx, dy)),
+            Line(start, end, _)
+            | CurveInward(start, end, _)
+            | CurveOutward(start, end, _)
+            | Rectangle(start, end) => {
+                start.nudge(dx, dy);
+                end.nudge(dx, dy);
+            }
+            // these are drawn relative to the cell of their point, which fractional points don't have.
+            // They are placed exactly instead, which also works off-canvas.
+            Text(at, ..) => {
+                let (x, y) = at.as_corner().grid_coords();
+                *at = Point::at(x + dx, y + dy);
+            }
+            CenteredText(at, ..) | BigCircle(at) | SmallCircle(at) => {
+                let (x, y) = at.as_centered().grid_coords();
+                *at = Point::centered_at(x + dx, y + dy);
+            }
+            Dot(at)
+            | BigDot(at)
+            | Arc { center: at, .. }
+            | Sector { center: at, .. }
+            | Component { at, .. }
+            | RawSVG { at, .. } => at.nudge(dx, dy),
+            Image(region, ..)
+            | Ellipse(region)
+            | FittedText { region, .. }
+            | Tiling(region, ..) => region.nudge(dx, dy),
+            Boolean { a, b, .. } => {
+                a.nudge(dx, dy);
+                b.nudge(dx, dy);
             }
         }
     }
 
     pub fn position(&self) -> Point {
         match self {
+            Boolean { a, .. } => a.position(),
+            // empty paths are at the origin, like the default point
+            Path(segments) => segments
+                .iter()
+                .flat_map(|segment| segment.anchors())
+                .next()
+                .map_or_else(Point::default, |anchor| anchor.point),
             Polygon(at, ..)
             | Line(at, ..)
             | CurveInward(at, ..)
@@ -85,11 +641,17 @@ impl Shape {
             | BigDot(at)
             | BigCircle(at)
             | SmallCircle(at)
+            | Arc { center: at, .. }
+            | Sector { center: at, .. }
             | Component { at, .. }
-            | Image(Region { start: at, .. }, ..) => *at,
-            RawSVG { .. } => {
-                unimplemented!()
-            }
+            | RawSVG { at, .. }
+            | Image(Region { start: at, .. }, ..)
+            | Ellipse(Region { start: at, .. })
+            | Tiling(Region { start: at, .. }, ..)
+            | FittedText {
+                region: Region { start: at, .. },
+                ..
+            } => *at,
         }
     }
 
@@ -111,67 +673,118 @@ impl Shape {
     pub fn region(&self) -> Region {
         match self {
             Polygon(start, lines) => {
-                let mut region: Region = (start, start).into();
+                let mut points = vec![start.grid_coords()];
+                let mut from = *start;
                 for line in lines {
-                    match line {
-                        LineSegment::InwardCurve(anchor)
-                        | LineSegment::OutwardCurve(anchor)
-                        | LineSegment::Straight(anchor) => {
-                            // println!(
-                            //     "extending region {} with {}",
-                            //     region,
-                            //     Region::from((start, anchor))
-                            // );
-                            region = *region.max(&(start, anchor).into())
-                        }
+                    let to = line.end();
+                    points.push(to.grid_coords());
+                    // curved segments can bulge out of the polygon's vertices bounding box
+                    if let Some(control) = line.control_point(from) {
+                        points.extend(quadratic_curve_extrema(
+                            from.grid_coords(),
+                            control,
+                            to.grid_coords(),
+                        ));
                     }
+                    from = to;
                 }
-                // println!("region for {:?} -> {}", self, region);
-                region
+
+                covering_points(&points)
             }
+            Path(segments) => path_region(segments),
+            // intersections and differences are always within the first shape
+            Boolean { op, a, b } => match op {
+                BooleanOperation::Union | BooleanOperation::Xor => {
+                    a.region().merge(&b.region())
+                }
+                BooleanOperation::Intersection | BooleanOperation::Difference => {
+                    a.region()
+                }
+            },
             Line(s, e, _) | CurveInward(s, e, _) | CurveOutward(s, e, _) => {
-                let (x1, y1, x2, y2) = (s.x(), s.y(), e.y(), e.x());
-
-                let region = Region::new(
-                    (x1.min(x2), y1.min(y2)),
-                    (x1.max(x2), y1.max(y2)),
-                )
-                .map_err(|e| {
-                    anyhow!("Could not construct region of {self:?}: {e:?}")
-                })
-                .unwrap();
-
+                covering_points(&[s.grid_coords(), e.grid_coords()])
+            }
+            Rectangle(start, end) => {
+                let region = Region::new(*start, *end).unwrap();
                 region.enlarged(
                     if region.width() > 1 { -1 } else { 0 },
                     if region.height() > 1 { -1 } else { 0 },
                 )
             }
-            Rectangle(start, end) => {
-                Region::new(*start, *end).unwrap().enlarged(-1, -1)
-            }
             Text(anchor, _, _)
             | CenteredText(anchor, ..)
             | Dot(anchor)
             | BigDot(anchor)
             | SmallCircle(anchor) => anchor.region(),
             BigCircle(center) => center.region(),
-            Image(region, ..) => *region,
+            Arc {
+                center,
+                radius_cells,
+                start,
+                end,
+            } => {
+                let extrema = arc_extrema(
+                    center.grid_coords(),
+                    *radius_cells,
+                    *start,
+                    *end,
+                );
+                covering_points(&extrema)
+            }
+            Sector {
+                center,
+                inner_radius_cells,
+                outer_radius_cells,
+                start,
+                end,
+            } => {
+                let center = center.grid_coords();
+                let mut extrema =
+                    arc_extrema(center, *outer_radius_cells, *start, *end);
+                // the inner arc never reaches further than the outer one, except at its endpoints
+                extrema.extend([*start, *end].map(|angle| {
+                    point_on_circle(center, *inner_radius_cells, angle)
+                }));
+                covering_points(&extrema)
+            }
+            Image(region, ..)
+            | Ellipse(region)
+            | FittedText { region, .. }
+            | Tiling(region, ..) => *region,
             Component { at, size, .. } => Region::from_topleft(*at, *size)
                 .expect("Invalid region for component"),
-            RawSVG { .. } => {
-                unimplemented!()
+            RawSVG {
+                at,
+                bounds: ((min_x, min_y), (max_x, max_y)),
+                ..
+            } => {
+                let (x, y) = at.grid_coords();
+                Region::covering((x + min_x, y + min_y), (x + max_x, y + max_y))
             }
         }
     }
 
     pub fn fillable(&self) -> bool {
-        !matches!(self, Line(..) | CurveInward(..) | CurveOutward(..))
+        !matches!(
+            self,
+            Line(..) | CurveInward(..) | CurveOutward(..) | Arc { .. }
+        )
     }
 
     pub fn hatchable(&self) -> bool {
-        self.fillable() && !matches!(self, Dot(..))
+        self.fillable() && !matches!(self, Dot(..) | Tiling(..))
+    }
+
+    /// Objects nested inside this shape, for shapes that are made of other objects
+    pub fn nested_objects(&self) -> Vec<&Object> {
+        match self {
+            Component { objects, .. } => objects.iter().collect(),
+            Tiling(_, tile, _) => vec![tile.as_ref()],
+            _ => vec![],
+        }
     }
 
+    /// For shapes other than lines, or points not on a grid corner, whether the point touches the shape, see `Shape::contains_point`.
     pub fn point_is_on_line(self, point: Point) -> bool {
         match (&self, point) {
             (Line(s, e, _), Point::Corner(x, y)) => {
@@ -187,8 +800,7 @@ impl Shape {
 
                 (m * x as f32 + p) as usize == y
             }
-            (Line(..), _) => panic!("Point type not supported"),
-            _ => panic!("{self:?} is not a line object"),
+            _ => self.contains_point(point),
         }
     }
 
@@ -200,7 +812,7 @@ impl Shape {
     }
 
     /// Check if this line intersects with another line.
-    /// Panics if either shape is not a line.
+    /// Other shapes are handled by `Shape::intersects`.
     ///
     /// ```
     /// use shapemaker::{Line, Point::Center};
@@ -254,12 +866,223 @@ impl Shape {
 
                 (a1 * b2) != (a2 * b1)
             }
-            _ => {
-                unimplemented!(
-                    "Intersection not implemented for {self:?} and {:?}",
-                    line.clone()
+            _ => self.intersects(&line),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        CellSize, Color, ColorMapping, ObjectSizes, geometry::assert_close,
+        rendering::renderable::SVGRenderable,
+    };
+
+    fn cells(shape: &Shape) -> ((usize, usize), (usize, usize)) {
+        let region = shape.region();
+        (region.start.xy(), region.end.xy())
+    }
+
+    #[test]
+    fn curves_bulge_on_either_side() {
+        let (start, end) = ((0.0, 0.0), (2.0, 0.0));
+        assert_eq!(curve_control_point(start, end, true), (1.0, -1.0));
+        assert_eq!(curve_control_point(start, end, false), (1.0, 1.0));
+
+        let (start, end) = ((0.0, 0.0), (0.0, 2.0));
+        assert_eq!(curve_control_point(start, end, true), (-1.0, 1.0));
+        assert_eq!(curve_control_point(start, end, false), (1.0, 1.0));
+    }
+
+    #[test]
+    fn curves_between_repeated_points() {
+        assert_eq!(
+            curve_control_point((1.0, 1.0), (1.0, 1.0), true),
+            (1.0, 1.0)
+        );
+
+        let polygon = Polygon(
+            Point::Corner(1, 1),
+            vec![
+                LineSegment::InwardCurve(Point::Corner(1, 1)),
+                LineSegment::OutwardCurve(Point::Corner(3, 1)),
+                LineSegment::OutwardCurve(Point::Corner(3, 1)),
+                LineSegment::Straight(Point::Corner(1, 3)),
+            ],
+        );
+        assert_eq!(cells(&polygon), ((1, 1), (2, 2)));
+        assert!(
+            polygon
+                .render_to_svg(
+                    ColorMapping::default(),
+                    &CellSize::square(10),
+                    ObjectSizes::default(),
+                    "polygon",
                 )
-            }
+                .is_ok()
+        );
+    }
+
+    #[test]
+    fn line_regions() {
+        let line = Line(Point::Corner(0, 0), Point::Corner(3, 1), 1.0);
+        assert_eq!(line.region().cells(), ((0, 0), (2, 0)));
+        let curve = CurveInward(Point::Corner(1, 4), Point::Corner(0, 0), 1.0);
+        assert_eq!(curve.region().cells(), ((0, 0), (0, 3)));
+    }
+
+    #[test]
+    fn polygon_regions() {
+        let triangle = |segment: fn(Point) -> LineSegment| {
+            Polygon(
+                Point::Corner(0, 2),
+                vec![
+                    segment(Point::Corner(2, 2)),
+                    LineSegment::Straight(Point::Corner(2, 4)),
+                ],
+            )
+        };
+
+        // vertices are points of the outline, like the ones of paths
+        assert_eq!(cells(&triangle(LineSegment::Straight)), ((0, 2), (1, 3)));
+        // the curve bulges half a cell above the first edge
+        assert_eq!(cells(&triangle(LineSegment::InwardCurve)), ((0, 1), (1, 3)));
+
+        let path = PathBuilder::new((0, 2))
+            .line_to((2, 2))
+            .line_to((2, 4))
+            .close()
+            .build();
+        assert_eq!(cells(&triangle(LineSegment::Straight)), cells(&path));
+    }
+
+    #[test]
+    fn raw_svg_regions_follow_their_content() {
+        let mut shape = Shape::raw_svg(
+            r##"<rect x="1" y="0.5" width="2" height="1" fill="#c0ffee"/>"##,
+            "#c0ffee",
+        )
+        .unwrap();
+        assert_eq!(cells(&shape), ((1, 0), (2, 1)));
+
+        shape.translate(2, 3);
+        assert_eq!(shape.position(), Point::Corner(2, 3));
+        assert_eq!(cells(&shape), ((3, 3), (4, 4)));
+    }
+
+    #[test]
+    fn raw_svg_is_positioned_and_filled() {
+        let mut shape = Shape::raw_svg(
+            r##"<path d="M 0 0 L 1 1" fill="#c0ffee"/>"##,
+            "#c0ffee",
+        )
+        .unwrap();
+        shape.translate(1, 2);
+
+        let rendered = Object::from(shape)
+            .colored(Color::Red)
+            .render_to_svg(
+                ColorMapping::default(),
+                &CellSize::square(10),
+                ObjectSizes::default(),
+                "raw",
+            )
+            .unwrap()
+            .to_string();
+        assert!(rendered.contains("translate(10 20) scale(10 10)"));
+        assert!(!rendered.contains("#c0ffee"));
+    }
+
+    #[test]
+    fn arc_and_sector_regions() {
+        let arc = |start: f32, end: f32| Arc {
+            center: Point::Corner(2, 2),
+            radius_cells: 2.0,
+            start: Angle::from_degrees(start),
+            end: Angle::from_degrees(end),
+        };
+        assert_eq!(cells(&arc(0.0, 360.0)), ((0, 0), (3, 3)));
+        // from 3 o'clock to 6 o'clock
+        assert_eq!(cells(&arc(0.0, 90.0)), ((2, 2), (3, 3)));
+        // going counter-clockwise, through 12 o'clock
+        assert_eq!(cells(&arc(0.0, -90.0)), ((2, 0), (3, 1)));
+
+        let sector = Sector {
+            center: Point::Corner(2, 2),
+            inner_radius_cells: 1.0,
+            outer_radius_cells: 2.0,
+            start: Angle::from_degrees(0.0),
+            end: Angle::from_degrees(90.0),
+        };
+        assert_eq!(cells(&sector), ((2, 2), (3, 3)));
+    }
+
+    #[test]
+    fn ellipses_cover_their_region() {
+        let region = Region::from(((1, 2), (4, 3)));
+        assert_eq!(
+            cells(&Ellipse(region)),
+            (region.start.xy(), region.end.xy())
+        );
+    }
+
+    /// Vertices of a path made of straight lines, in grid units
+    fn vertices(shape: &Shape) -> Vec<(f32, f32)> {
+        let Path(segments) = shape else {
+            panic!("Expected Path, got {shape:?}");
+        };
+        segments
+            .iter()
+            .flat_map(|segment| segment.anchors())
+            .map(|anchor| anchor.grid_coords())
+            .collect()
+    }
+
+    #[test]
+    fn regular_polygons() {
+        let square = Shape::regular_polygon(
+            Point::Corner(2, 2),
+            2.0,
+            4,
+            Angle::from_degrees(0.0),
+        );
+        let corners = vertices(&square);
+        assert_eq!(corners.len(), 4);
+        for (vertex, expected) in corners.into_iter().zip([
+            (4.0, 2.0),
+            (2.0, 4.0),
+            (0.0, 2.0),
+            (2.0, 0.0),
+        ]) {
+            assert_close(vertex, expected);
+        }
+        assert_eq!(cells(&square), ((0, 0), (3, 3)));
+
+        let hexagon = Shape::regular_polygon(
+            Point::Corner(5, 5),
+            1.5,
+            6,
+            Angle::from_degrees(30.0),
+        );
+        assert_eq!(vertices(&hexagon).len(), 6);
+        for vertex in vertices(&hexagon) {
+            let distance = (vertex.0 - 5.0).hypot(vertex.1 - 5.0);
+            assert!((distance - 1.5).abs() < 1e-4);
+        }
+    }
+
+    #[test]
+    fn stars_alternate_between_tips_and_inner_vertices() {
+        let star = Shape::star(Point::Corner(3, 3), 3.0, 1.0, 5);
+        let points = vertices(&star);
+        assert_eq!(points.len(), 10);
+        // the first tip points up
+        assert_close(points[0], (3.0, 0.0));
+        for (i, (x, y)) in points.into_iter().enumerate() {
+            let distance = (x - 3.0).hypot(y - 3.0);
+            let expected = if i % 2 == 0 { 3.0 } else { 1.0 };
+            assert!((distance - expected).abs() < 1e-4);
         }
     }
 }
@@ -0,0 +1,249 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::{Object, Point, Region};
+
+/// Width and height of a bucket, in cells
+const BUCKET_SIZE: usize = 4;
+
+/// Objects of a layer, bucketed by the part of the grid they occupy.
+/// Spatial queries then only have to look at the objects of the buckets they cover,
+/// instead of computing the region of every object of the layer.
+///
+/// Entries are kept up to date by `Layer`'s methods. Objects changed through a mutable
+/// reference are marked stale, and indexed again right before the next query.
+#[derive(Debug, Clone, Default)]
+pub struct SpatialIndex {
+    entries: HashMap<String, IndexEntry>,
+    buckets: HashMap<(isize, isize), HashSet<String>>,
+    stale: HashSet<String>,
+    needs_rebuild: bool,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct IndexEntry {
+    pub region: Region,
+    pub position: Point,
+}
+
+impl IndexEntry {
+    /// Distance from the point to the closest cell of the region, in cells
+    pub fn distance_to(&self, point: Point) -> f32 {
+        let gap = |value: isize, start: isize, end: isize| {
+            (start - value).max(value - end).max(0) as f32
+        };
+
+        let (x, y) = point.xy::<isize>();
+        let ((start_x, start_y), (end_x, end_y)) = self.region.cells();
+        let dx = gap(x, start_x, end_x);
+        let dy = gap(y, start_y, end_y);
+        (dx * dx + dy * dy).sqrt()
+    }
+
+    /// Cells used to put the entry in buckets.
+    /// Includes the position, which is not always within the region (e.g. the center of an arc).
+    fn extent(&self) -> Region {
+        self.region
+            .merge(&Region::from((self.position, self.position)))
+    }
+}
+
+impl SpatialIndex {
+    pub fn insert(&mut self, name: &str, object: &Object) {
+        self.remove(name);
+
+        let entry = IndexEntry {
+            region: object.region(),
+            position: object.position(),
+        };
+
+        for bucket in buckets_of(&entry.extent()) {
+            self.buckets
+                .entry(bucket)
+                .or_default()
+                .insert(name.to_string());
+        }
+
+        self.entries.insert(name.to_string(), entry);
+    }
+
+    pub fn remove(&mut self, name: &str) {
+        self.stale.remove(name);
+
+        let Some(entry) = self.entries.remove(name) else {
+            return;
+        };
+
+        for bucket in buckets_of(&entry.extent()) {
+            if let Some(names) = self.buckets.get_mut(&bucket) {
+                names.remove(name);
+                if names.is_empty() {
+                    self.buckets.remove(&bucket);
+                }
+            }
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.buckets.clear();
+        self.stale.clear();
+        self.needs_rebuild = false;
+    }
+
+    /// Marks the object as possibly changed since it was indexed
+    pub fn invalidate(&mut self, name: &str) {
+        self.stale.insert(name.to_string());
+    }
+
+    /// Marks every object as possibly changed since they were indexed
+    pub fn invalidate_all(&mut self) {
+        self.needs_rebuild = true;
+    }
+
+    /// Indexes again the objects that were invalidated, so that queries reflect the current state of `objects`
+    pub fn refresh(&mut self, objects: &HashMap<String, Object>) {
+        // objects added or removed without going through the layer's methods
+        if self.needs_rebuild || objects.len() != self.entries.len() {
+            self.clear();
+            for (name, object) in objects {
+                self.insert(name, object);
+            }
+            return;
+        }
+
+        for name in std::mem::take(&mut self.stale) {
+            match objects.get(&name) {
+                Some(object) => self.insert(&name, object),
+                None => self.remove(&name),
+            }
+        }
+    }
+
+    pub fn entry(&self, name: &str) -> Option<&IndexEntry> {
+        self.entries.get(name)
+    }
+
+    /// Objects that might touch the region, with their entries. Every object touching the region is included.
+    pub fn candidates(
+        &self,
+        region: &Region,
+    ) -> impl Iterator<Item = (&String, &IndexEntry)> {
+        buckets_of(region)
+            .filter_map(|bucket| self.buckets.get(&bucket))
+            .flatten()
+            .collect::<HashSet<_>>()
+            .into_iter()
+            .map(|name| (name, &self.entries[name]))
+    }
+
+    /// Objects whose region is the closest to the point. Several objects are returned in case of a tie.
+    pub fn nearest(&self, point: Point) -> Vec<&String> {
+        let Some(farthest) = self
+            .buckets
+            .keys()
+            .map(|&bucket| ring_distance(bucket_of(point), bucket))
+            .max()
+        else {
+            return vec![];
+        };
+
+        let mut best: Option<(f32, Vec<&String>)> = None;
+
+        for ring in 0..=farthest {
+            for bucket in ring_around(bucket_of(point), ring) {
+                for name in self.buckets.get(&bucket).into_iter().flatten() {
+                    let distance = self.entries[name].distance_to(point);
+                    match &mut best {
+                        Some((closest, _)) if distance > *closest => {}
+                        Some((closest, names)) if distance == *closest => {
+                            if !names.contains(&name) {
+                                names.push(name)
+                            }
+                        }
+                        _ => best = Some((distance, vec![name])),
+                    }
+                }
+            }
+
+            // buckets of the next rings are all farther away than this
+            if best.as_ref().is_some_and(|(closest, _)| {
+                *closest <= (ring * BUCKET_SIZE) as f32
+            }) {
+                break;
+            }
+        }
+
+        best.map(|(_, names)| names).unwrap_or_default()
+    }
+}
+
+fn bucket_of(point: Point) -> (isize, isize) {
+    let (x, y) = point.xy::<isize>();
+    let size = BUCKET_SIZE as isize;
+    (x.div_euclid(size), y.div_euclid(size))
+}
+
+fn buckets_of(region: &Region) -> impl Iterator<Item = (isize, isize)> {
+    let (start, end) = (bucket_of(region.start), bucket_of(region.end));
+    (start.1..=end.1).flat_map(move |y| (start.0..=end.0).map(move |x| (x, y)))
+}
+
+fn ring_distance((ax, ay): (isize, isize), (bx, by): (isize, isize)) -> usize {
+    ax.abs_diff(bx).max(ay.abs_diff(by))
+}
+
+/// Buckets at exactly `ring` buckets away from `center`, in every direction
+fn ring_around(
+    center: (isize, isize),
+    ring: usize,
+) -> impl Iterator<Item = (isize, isize)> {
+    let ring = ring as isize;
+    let start = (center.0 - ring, center.1 - ring);
+    let end = (center.0 + ring, center.1 + ring);
+
+    (start.1..=end.1)
+        .flat_map(move |y| (start.0..=end.0).map(move |x| (x, y)))
+        .filter(move |&bucket| ring_distance(center, bucket) == ring as usize)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Shape;
+
+    fn dot(x: usize, y: usize) -> Object {
+        Shape::Dot(Point::Corner(x, y)).into()
+    }
+
+    #[test]
+    fn only_stale_entries_are_refreshed() {
+        let mut objects = HashMap::from([
+            ("a".to_string(), dot(0, 0)),
+            ("b".to_string(), dot(1, 1)),
+        ]);
+        let mut index = SpatialIndex::default();
+        index.refresh(&objects);
+
+        objects.insert("a".to_string(), dot(8, 8));
+        objects.insert("b".to_string(), dot(9, 9));
+        index.invalidate("a");
+        index.refresh(&objects);
+
+        assert_eq!(index.entry("a").unwrap().position, Point::Corner(8, 8));
+        // not marked as stale, so still where it was
+        assert_eq!(index.entry("b").unwrap().position, Point::Corner(1, 1));
+        assert!(!index.needs_rebuild);
+    }
+
+    #[test]
+    fn moved_entries_change_buckets() {
+        let mut index = SpatialIndex::default();
+        index.insert("a", &dot(0, 0));
+        index.insert("a", &dot(10, 10));
+
+        let far = Region::from(((8, 8), (11, 11)));
+        let near = Region::from(((0, 0), (1, 1)));
+        assert_eq!(index.candidates(&far).count(), 1);
+        assert_eq!(index.candidates(&near).count(), 0);
+    }
+}
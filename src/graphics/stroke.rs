@@ -0,0 +1,99 @@
+use crate::{Color, Fill, geometry::hash_floats};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum LineCap {
+    #[default]
+    Butt,
+    Round,
+    Square,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum LineJoin {
+    #[default]
+    Miter,
+    Round,
+    Bevel,
+}
+
+/// Outline of an object. Can be used on filled shapes to draw both a fill and an outline.
+#[derive(Debug, Clone)]
+pub struct Stroke {
+    pub fill: Fill,
+    /// Width of the stroke, in pixels
+    pub width: f32,
+    /// Lengths of alternating dashes and gaps, in pixels. Empty for a solid line.
+    pub dash_array: Vec<f32>,
+    pub dash_offset: f32,
+    pub cap: LineCap,
+    pub join: LineJoin,
+    pub miter_limit: f32,
+}
+
+impl std::hash::Hash for Stroke {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        (&self.fill, self.cap, self.join).hash(state);
+        hash_floats(&self.dash_array, state);
+        hash_floats(&[self.width, self.dash_offset, self.miter_limit], state);
+    }
+}
+
+impl Default for Stroke {
+    fn default() -> Self {
+        Self {
+            fill: Fill::Solid(Color::default()),
+            width: 1.0,
+            dash_array: vec![],
+            dash_offset: 0.0,
+            cap: LineCap::default(),
+            join: LineJoin::default(),
+            miter_limit: 4.0,
+        }
+    }
+}
+
+impl Stroke {
+    pub fn new(fill: Fill, width: f32) -> Self {
+        Self {
+            fill,
+            width,
+            ..Self::default()
+        }
+    }
+
+    pub fn solid(color: Color, width: f32) -> Self {
+        Self::new(Fill::Solid(color), width)
+    }
+
+    pub fn dashed(self, dash_array: Vec<f32>) -> Self {
+        Self { dash_array, ..self }
+    }
+
+    pub fn dash_offset(self, dash_offset: f32) -> Self {
+        Self {
+            dash_offset,
+            ..self
+        }
+    }
+
+    pub fn capped(self, cap: LineCap) -> Self {
+        Self { cap, ..self }
+    }
+
+    pub fn joined(self, join: LineJoin) -> Self {
+        Self { join, ..self }
+    }
+
+    pub fn miter_limit(self, miter_limit: f32) -> Self {
+        Self {
+            miter_limit,
+            ..self
+        }
+    }
+}
+
+impl Color {
+    pub fn stroke(self, width: f32) -> Stroke {
+        Stroke::solid(self, width)
+    }
+}
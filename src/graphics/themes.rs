@@ -0,0 +1,409 @@
+use anyhow::{Result, anyhow};
+use serde_json::Value;
+
+use crate::ColorMapping;
+
+/// Default colors taken from the 16 colors of base16 schemes, following base16's styling guidelines
+const BASE16_COLORS: [(&str, &str); 13] = [
+    ("black", "base00"),
+    ("background", "base00"),
+    ("gray", "base03"),
+    ("foreground", "base05"),
+    ("white", "base07"),
+    ("red", "base08"),
+    ("orange", "base09"),
+    ("yellow", "base0A"),
+    ("green", "base0B"),
+    ("cyan", "base0C"),
+    ("blue", "base0D"),
+    ("purple", "base0E"),
+    ("brown", "base0F"),
+];
+
+/// Colors of VS Code themes used as swatches, besides their own names
+const VSCODE_COLORS: [(&str, &str); 2] = [
+    ("background", "editor.background"),
+    ("foreground", "editor.foreground"),
+];
+
+/// Terminal colors of VS Code themes, named like in Windows Terminal color schemes
+const ANSI_COLORS: [(&str, &str); 8] = [
+    ("Black", "black"),
+    ("Red", "red"),
+    ("Green", "green"),
+    ("Yellow", "yellow"),
+    ("Blue", "blue"),
+    ("Magenta", "purple"),
+    ("Cyan", "cyan"),
+    ("White", "white"),
+];
+
+impl ColorMapping {
+    /// Palette from a base16 scheme, in YAML. The 16 colors are swatches named `base00` to `base0F`,
+    /// and are used for the default colors too, along with a `background` and `foreground`.
+    /// Both the original format and tinted-theming's, where colors are under `palette:`, are supported.
+    ///
+    /// ```
+    /// use shapemaker::ColorMapping;
+    ///
+    /// let palette = ColorMapping::from_base16_yaml(r#"
+    /// scheme: "Default Dark"
+    /// base00: "181818"
+    /// base08: "ab4642" # red
+    /// "#).unwrap();
+    /// assert_eq!(palette.get("base08").unwrap(), "#ab4642");
+    /// assert_eq!(palette.get("red").unwrap(), "#ab4642");
+    /// assert_eq!(palette.get("background").unwrap(), "#181818");
+    /// ```
+    pub fn from_base16_yaml(content: &str) -> Result<ColorMapping> {
+        let mut mapping = ColorMapping::default();
+
+        for line in content.lines() {
+            let Some((key, value)) = line.trim().split_once(':') else {
+                continue;
+            };
+            let key = key.trim().trim_matches(['"', '\'']);
+            let is_base = key.len() == 6
+                && key.starts_with("base0")
+                && key.chars().last().is_some_and(|c| c.is_ascii_hexdigit());
+            if !is_base {
+                continue;
+            }
+
+            let value = value.trim();
+            let value = match value.chars().next() {
+                Some(quote @ ('"' | '\'')) => {
+                    value[1..].split(quote).next().unwrap_or_default()
+                }
+                // unquoted # start comments
+                _ => value.split(" #").next().unwrap_or_default().trim(),
+            };
+
+            // the original format leaves out the #
+            if value.len() == 6 && value.chars().all(|c| c.is_ascii_hexdigit()) {
+                mapping.set_validated(key, &format!("#{value}"))?;
+            } else {
+                mapping.set_validated(key, value)?;
+            }
+        }
+
+        if !mapping.names().iter().any(|name| name.starts_with("base0")) {
+            return Err(anyhow!("No base16 colors (base00 to base0F) found"));
+        }
+
+        for (name, base) in BASE16_COLORS {
+            if let Some(value) = mapping.get(base) {
+                mapping.set(name, &value);
+            }
+        }
+
+        Ok(mapping)
+    }
+
+    /// Palette from a VS Code color theme, in JSON with comments and trailing commas allowed. Workbench colors are swatches with their own names, such as `editor.background`,
+    /// except for the ones set to `null`.
+    /// Terminal colors are used for the default colors, and are named like in Windows Terminal color schemes too: `brightRed`, `brightPurple`, etc.
+    /// Scopes of token colors are swatches of their foreground, such as `comment` or `keyword.control`.
+    pub fn from_vscode_theme(content: &str) -> Result<ColorMapping> {
+        ColorMapping::from_vscode_json(&parse_theme(content)?)
+    }
+
+    /// Palette from a TextMate theme converted to JSON. Global settings are swatches with their own names, such as `background` or `caret`,
+    /// and scopes are swatches of their foreground, such as `comment` or `keyword.control`.
+    pub fn from_tmtheme(content: &str) -> Result<ColorMapping> {
+        ColorMapping::from_tmtheme_json(&parse_theme(content)?)
+    }
+
+    pub(crate) fn from_vscode_json(theme: &Value) -> Result<ColorMapping> {
+        let mut mapping = ColorMapping::default();
+
+        for (name, value) in theme
+            .get("colors")
+            .and_then(Value::as_object)
+            .into_iter()
+            .flatten()
+        {
+            // null resets a color to VS Code's default
+            if !value.is_null() {
+                mapping.set_validated(name, as_color(name, value)?)?;
+            }
+        }
+
+        for (name, key) in VSCODE_COLORS {
+            if let Some(value) = mapping.get(key) {
+                mapping.set(name, &value);
+            }
+        }
+
+        for (ansi, name) in ANSI_COLORS {
+            if let Some(value) = mapping.get(&format!("terminal.ansi{ansi}")) {
+                mapping.set(name, &value);
+            }
+            if let Some(value) =
+                mapping.get(&format!("terminal.ansiBright{ansi}"))
+            {
+                mapping.set(&format!("bright{}", capitalized(name)), &value);
+            }
+        }
+
+        if let Some(rules) = theme.get("tokenColors").and_then(Value::as_array) {
+            mapping.set_scopes(rules)?;
+        }
+
+        Ok(mapping)
+    }
+
+    pub(crate) fn from_tmtheme_json(theme: &Value) -> Result<ColorMapping> {
+        let mut mapping = ColorMapping::default();
+        let rules = theme
+            .get("settings")
+            .and_then(Value::as_array)
+            .ok_or_else(|| anyhow!("A tmTheme must have a list of settings"))?;
+
+        // global settings are the ones without a scope
+        for rule in rules.iter().filter(|rule| rule.get("scope").is_none()) {
+            for (name, value) in rule
+                .get("settings")
+                .and_then(Value::as_object)
+                .into_iter()
+                .flatten()
+            {
+                mapping.set_validated(name, as_color(name, value)?)?;
+            }
+        }
+
+        mapping.set_scopes(rules)?;
+        Ok(mapping)
+    }
+
+    /// Sets a swatch for each scope of the TextMate rules with a foreground color
+    fn set_scopes(&mut self, rules: &[Value]) -> Result<()> {
+        for rule in rules {
+            let Some(foreground) = rule
+                .get("settings")
+                .and_then(|s| s.get("foreground"))
+                .filter(|foreground| !foreground.is_null())
+            else {
+                continue;
+            };
+
+            // scopes are either a list or comma-separated
+            let scopes: Vec<&str> = match rule.get("scope") {
+                Some(Value::String(scopes)) => scopes.split(',').collect(),
+                Some(Value::Array(scopes)) => {
+                    scopes.iter().filter_map(Value::as_str).collect()
+                }
+                _ => continue,
+            };
+
+            for scope in scopes.into_iter().map(str::trim) {
+                self.set_validated(scope, as_color(scope, foreground)?)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+fn parse_theme(content: &str) -> Result<Value> {
+    serde_json::from_str(&without_jsonc(content))
+        .map_err(|e| anyhow!("Invalid theme JSON: {e}"))
+}
+
+/// Strips the comments and trailing commas JSONC allows, as VS Code themes often have them
+pub(crate) fn without_jsonc(content: &str) -> String {
+    let mut json = String::with_capacity(content.len());
+    let mut chars = content.chars().peekable();
+    let mut in_string = false;
+
+    while let Some(c) = chars.next() {
+        if in_string {
+            json.push(c);
+            match c {
+                '\\' => json.extend(chars.next()),
+                '"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+
+        match (c, chars.peek()) {
+            ('"', _) => {
+                in_string = true;
+                json.push(c);
+            }
+            ('/', Some('/')) => while chars.next_if(|&c| c != '\n').is_some() {},
+            ('/', Some('*')) => {
+                chars.next();
+                let mut previous = None;
+                for c in chars.by_ref() {
+                    if previous == Some('*') && c == '/' {
+                        break;
+                    }
+                    previous = Some(c);
+                }
+            }
+            _ => json.push(c),
+        }
+    }
+
+    // with comments gone, a trailing comma is only followed by whitespace
+    let mut stripped = String::with_capacity(json.len());
+    let mut in_string = false;
+    let mut escaped = false;
+    for (i, c) in json.char_indices() {
+        if in_string {
+            in_string = escaped || c != '"';
+            escaped = !escaped && c == '\\';
+        } else if c == '"' {
+            in_string = true;
+        } else if c == ',' && json[i + 1..].trim_start().starts_with(['}', ']']) {
+            continue;
+        }
+        stripped.push(c);
+    }
+    stripped
+}
+
+fn as_color<'a>(name: &str, value: &'a Value) -> Result<&'a str> {
+    value.as_str().ok_or_else(|| {
+        anyhow!("Invalid color for {name:?}: {value} is not a string")
+    })
+}
+
+fn capitalized(name: &str) -> String {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .map(|first| first.to_uppercase().chain(chars).collect())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn base16_schemes() {
+        let palette = ColorMapping::from_base16_yaml(
+            "scheme: \"Ocean\"
+palette:
+  base00: '#2b303b'
+  base05: \"c0c5ce\" # text
+  base08: bf616a # red
+  base0D: 8fa1b3
+",
+        )
+        .unwrap();
+
+        assert_eq!(palette.get("base00").unwrap(), "#2b303b");
+        assert_eq!(palette.get("background").unwrap(), "#2b303b");
+        assert_eq!(palette.get("black").unwrap(), "#2b303b");
+        assert_eq!(palette.get("foreground").unwrap(), "#c0c5ce");
+        assert_eq!(palette.get("red").unwrap(), "#bf616a");
+        assert_eq!(palette.get("blue").unwrap(), "#8fa1b3");
+        // colors the scheme doesn't have keep their default
+        assert_eq!(palette.get("green").unwrap(), "green");
+    }
+
+    #[test]
+    fn invalid_base16_schemes() {
+        assert!(ColorMapping::from_base16_yaml("scheme: nothing").is_err());
+        let error = ColorMapping::from_base16_yaml("base08: banana").unwrap_err();
+        assert!(error.to_string().contains("base08"));
+    }
+
+    #[test]
+    fn vscode_themes() {
+        let palette = ColorMapping::from_vscode_theme(
+            r##"{
+                "colors": {
+                    "editor.background": "#292d3e",
+                    "terminal.ansiRed": "#f07178",
+                    "terminal.ansiBrightMagenta": "#c792ea"
+                },
+                "tokenColors": [
+                    { "scope": "comment, string.quoted", "settings": { "foreground": "#676e95" } },
+                    { "scope": ["keyword.control"], "settings": { "foreground": "#c792ea" } },
+                    { "scope": "markup.bold", "settings": { "fontStyle": "bold" } }
+                ]
+            }"##,
+        )
+        .unwrap();
+
+        assert_eq!(palette.get("editor.background").unwrap(), "#292d3e");
+        assert_eq!(palette.get("background").unwrap(), "#292d3e");
+        assert_eq!(palette.get("red").unwrap(), "#f07178");
+        assert_eq!(palette.get("brightPurple").unwrap(), "#c792ea");
+        assert_eq!(palette.get("comment").unwrap(), "#676e95");
+        assert_eq!(palette.get("string.quoted").unwrap(), "#676e95");
+        assert_eq!(palette.get("keyword.control").unwrap(), "#c792ea");
+        assert_eq!(palette.get("markup.bold"), None);
+    }
+
+    #[test]
+    fn vscode_themes_with_comments() {
+        let palette = ColorMapping::from_vscode_theme(
+            r##"{
+                // workbench colors
+                "colors": {
+                    "editor.background": "#292d3e", /* dark */
+                    "editor.foreground": null,
+                    "editor.selectionBackground": "#ffffff", // light
+                },
+                "tokenColors": [
+                    { "scope": "comment", "settings": { "foreground": "#676e95", }, },
+                    { "scope": "string", "settings": { "foreground": null } },
+                ],
+            }"##,
+        )
+        .unwrap();
+
+        assert_eq!(palette.get("background").unwrap(), "#292d3e");
+        assert_eq!(palette.get("editor.foreground"), None);
+        assert_eq!(palette.get("foreground"), None);
+        assert_eq!(palette.get("comment").unwrap(), "#676e95");
+        assert_eq!(palette.get("string"), None);
+        assert_eq!(
+            without_jsonc(r#"{ "a": "x,}", "b": "\"//", }"#),
+            r#"{ "a": "x,}", "b": "\"//" }"#
+        );
+    }
+
+    #[test]
+    fn json_palettes_with_a_colors_swatch() {
+        let palette = ColorMapping::from_json(
+            r##"{ "name": "Ocean", "colors": "#2b303b" }"##,
+        )
+        .unwrap();
+        assert_eq!(palette.get("colors").unwrap(), "#2b303b");
+    }
+
+    #[test]
+    fn tmthemes() {
+        let theme = r##"{
+            "settings": [
+                { "settings": { "background": "#272822", "caret": "#f8f8f0" } },
+                { "scope": "keyword", "settings": { "foreground": "#f92672" } }
+            ]
+        }"##;
+        let palette = ColorMapping::from_tmtheme(theme).unwrap();
+        assert_eq!(palette.get("background").unwrap(), "#272822");
+        assert_eq!(palette.get("caret").unwrap(), "#f8f8f0");
+        assert_eq!(palette.get("keyword").unwrap(), "#f92672");
+
+        // JSON palettes are recognized as themes too
+        assert_eq!(ColorMapping::from_json(theme).unwrap(), palette);
+    }
+
+    #[test]
+    fn invalid_themes() {
+        assert!(ColorMapping::from_tmtheme("{}").is_err());
+        assert!(ColorMapping::from_vscode_theme("not json").is_err());
+
+        let error = ColorMapping::from_vscode_theme(
+            r#"{ "colors": { "editor.background": 12 } }"#,
+        )
+        .unwrap_err();
+        assert!(error.to_string().contains("editor.background"));
+    }
+}
@@ -3,6 +3,8 @@ use wasm_bindgen::prelude::*;
 
 use slug::slugify;
 
+use crate::geometry::hash_floats;
+
 #[cfg_attr(feature = "web", wasm_bindgen)]
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum TransformationType {
@@ -20,6 +22,21 @@ pub enum Transformation {
     Matrix(f32, f32, f32, f32, f32, f32),
 }
 
+impl std::hash::Hash for Transformation {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        std::mem::discriminant(self).hash(state);
+        match *self {
+            Transformation::Scale(x, y) | Transformation::Skew(x, y) => {
+                hash_floats(&[x, y], state)
+            }
+            Transformation::Rotate(angle) => hash_floats(&[angle], state),
+            Transformation::Matrix(a, b, c, d, e, f) => {
+                hash_floats(&[a, b, c, d, e, f], state)
+            }
+        }
+    }
+}
+
 impl Transformation {
     pub fn name(&self) -> String {
         match self {
@@ -1,16 +1,16 @@
 #![allow(uncommon_codepoints)]
 
 pub fn enabled_features() -> Vec<&'static str> {
-    let mut features = vec![];
-    #[cfg(feature = "vst")]
-    features.push("vst");
-    #[cfg(feature = "video")]
-    features.push("video");
-    #[cfg(feature = "web")]
-    features.push("web");
-    #[cfg(feature = "video-server")]
-    features.push("video-server");
-    features
+    vec![
+        #[cfg(feature = "vst")]
+        "vst",
+        #[cfg(feature = "video")]
+        "video",
+        #[cfg(feature = "web")]
+        "web",
+        #[cfg(feature = "video-server")]
+        "video-server",
+    ]
 }
 
 pub mod geometry;
@@ -27,12 +27,15 @@ pub mod wasm;
 pub mod vst;
 
 pub use geometry::{
-    Angle, Axis, CenterPoint, Containable, CornerPoint, Norm, Point, Region,
+    Angle, Axis, CellSize, CenterPoint, Containable, CornerPoint,
+    FractionalPoint, Norm, Point, Region, Topology,
 };
 pub use graphics::{
-    Canvas, Color, Color::*, ColorMapping, Fill, FillOperations, Filter,
-    FilterType, Layer, LineSegment, Object, ObjectSizes, Shape, Shape::*,
-    Transformation,
+    Anchor, BlendMode, BooleanOperation, Canvas, Clip, Color, Color::*,
+    ColorMapping, Fill, FillOperations, Filter, FilterType, GradientStop,
+    HorizontalAlignment, Layer, LineCap, LineJoin, LineSegment, Mask, MaskMode,
+    Object, ObjectSizes, PathBuilder, PathSegment, Rgba, Shape, Shape::*,
+    SpatialIndex, Stroke, TilingOptions, Transformation, VerticalAlignment,
 };
 pub use rendering::{
     CSSRenderable, SVGAttributesRenderable, SVGRenderable, fonts,
@@ -2,6 +2,9 @@ use crate::{Canvas, Fill, Layer, Object, Region, Shape};
 use rand::{Rng, distr::uniform::SampleRange};
 use std::collections::HashMap;
 
+/// Number of shapes tried for each object when they can't overlap, see `Canvas::random_objects_can_overlap`
+const PLACEMENT_ATTEMPTS: usize = 100;
+
 impl Canvas {
     pub fn random_layer(&mut self, rng: &mut impl Rng, name: &str) -> Layer {
         self.random_layer_within(rng, name, &self.world_region.clone())
@@ -33,17 +36,25 @@ impl Canvas {
     ) -> Layer {
         let mut objects: HashMap<String, Object> = HashMap::new();
         for i in 0..count {
-            let object = Shape::random_curve_within(
-                rng,
-                region,
-                self.object_sizes.default_line_width,
-            );
+            let Some(object) = self.random_placement(rng, &objects, |rng| {
+                Shape::random_curve_within(
+                    rng,
+                    region,
+                    self.object_sizes.default_line_width,
+                )
+            }) else {
+                continue;
+            };
             objects.insert(
                 format!("{}#{}", layer_name, i),
                 Object::from((
                     object,
                     if rng.random_bool(0.5) {
-                        Some(Fill::random_solid(rng, self.background))
+                        Some(Fill::random_solid(
+                            rng,
+                            self.background.clone(),
+                            &self.colormap,
+                        ))
                     } else {
                         None
                     },
@@ -51,12 +62,9 @@ impl Canvas {
             );
         }
 
-        Layer {
-            object_sizes: self.object_sizes,
-            name: layer_name.to_owned(),
-            objects,
-            hidden: false,
-        }
+        let mut layer = Layer::with_objects(layer_name, objects);
+        layer.object_sizes = self.object_sizes;
+        layer
     }
 
     pub fn random_curves_within(
@@ -80,29 +88,38 @@ impl Canvas {
         let number_of_objects =
             rng.random_range(self.objects_count_range.clone());
         for i in 0..number_of_objects {
-            let object = Shape::random(
-                rng,
-                region,
-                self.object_sizes.default_line_width,
-                self.polygon_vertices_range.clone(),
-            );
+            let Some(object) = self.random_placement(rng, &objects, |rng| {
+                Shape::random(
+                    rng,
+                    region,
+                    self.object_sizes.default_line_width,
+                    self.polygon_vertices_range.clone(),
+                )
+            }) else {
+                continue;
+            };
             let hatchable = object.hatchable();
             objects.insert(
                 format!("{}#{}", name, i),
                 object.filled(if hatchable {
-                    Fill::random_hatches(rng, self.background)
+                    Fill::random_hatches(
+                        rng,
+                        self.background.clone(),
+                        &self.colormap,
+                    )
                 } else {
-                    Fill::random_solid(rng, self.background)
+                    Fill::random_solid(
+                        rng,
+                        self.background.clone(),
+                        &self.colormap,
+                    )
                 }),
             );
         }
 
-        Layer {
-            object_sizes: self.object_sizes,
-            name: name.to_string(),
-            objects,
-            hidden: false,
-        }
+        let mut layer = Layer::with_objects(name, objects);
+        layer.object_sizes = self.object_sizes;
+        layer
     }
 
     pub fn random_linelikes(
@@ -121,4 +138,25 @@ impl Canvas {
     pub fn random_region(&mut self, rng: &mut impl Rng) -> Region {
         Region::random(rng, &self.world_region.clone())
     }
+
+    /// Generates a shape that doesn't intersect any of the `placed` objects, unless `random_objects_can_overlap` is set.
+    /// Returns `None` if no such shape was found after a few attempts.
+    fn random_placement<R: Rng>(
+        &self,
+        rng: &mut R,
+        placed: &HashMap<String, Object>,
+        generate: impl Fn(&mut R) -> Shape,
+    ) -> Option<Shape> {
+        if self.random_objects_can_overlap {
+            return Some(generate(rng));
+        }
+
+        (0..PLACEMENT_ATTEMPTS)
+            .map(|_| generate(rng))
+            .find(|shape| {
+                placed
+                    .values()
+                    .all(|object| !object.shape.intersects(shape))
+            })
+    }
 }
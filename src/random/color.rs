@@ -1,23 +1,164 @@
-use crate::Color;
+use crate::{Color, ColorMapping};
 use rand::{
     Rng,
     distr::{Distribution, StandardUniform},
 };
 
+/// Minimum WCAG contrast ratio between random colors and the background, the one required for text
+pub const MIN_CONTRAST: f32 = 4.5;
+
 impl Distribution<Color> for StandardUniform {
     fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> Color {
-        let candidates = Color::all();
-        candidates[rng.random_range(0..candidates.len())]
+        let mut candidates = Color::all();
+        candidates.swap_remove(rng.random_range(0..candidates.len()))
     }
 }
 
 impl Color {
     pub fn random_except(rng: &mut impl Rng, except: Color) -> Self {
-        let candidates = Color::all()
-            .iter()
-            .filter(|&&c| c != except)
-            .cloned()
+        let mut candidates = Color::all()
+            .into_iter()
+            .filter(|c| *c != except)
             .collect::<Vec<_>>();
-        candidates[rng.random_range(0..candidates.len())]
+        candidates.swap_remove(rng.random_range(0..candidates.len()))
+    }
+
+    /// Random default color that stands out on the background, with the palette's values:
+    /// its contrast ratio with the background is at least `MIN_CONTRAST`.
+    /// When no color contrasts enough, picks among the most contrasting ones.
+    /// Colors whose value is not a CSS color are left out, see `Color::resolve`.
+    pub fn random_contrasting(
+        rng: &mut impl Rng,
+        background: Color,
+        mapping: &ColorMapping,
+    ) -> Self {
+        let Ok(backdrop) = background.resolve(mapping) else {
+            return Color::random_except(rng, background);
+        };
+
+        let contrasts: Vec<(Color, f32)> = Color::all()
+            .into_iter()
+            .filter(|color| *color != background)
+            .filter_map(|color| {
+                let rgba = color.resolve(mapping).ok()?;
+                Some((color, rgba.contrast_ratio(backdrop)))
+            })
+            .collect();
+
+        let best = contrasts
+            .iter()
+            .map(|&(_, contrast)| contrast)
+            .fold(0.0, f32::max);
+        let mut candidates: Vec<Color> = contrasts
+            .into_iter()
+            .filter(|&(_, contrast)| contrast >= MIN_CONTRAST.min(best))
+            .map(|(color, _)| color)
+            .collect();
+
+        if candidates.is_empty() {
+            return Color::random_except(rng, background);
+        }
+        candidates.swap_remove(rng.random_range(0..candidates.len()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Fill, Rgba};
+    use rand::{SeedableRng, rngs::SmallRng};
+
+    fn contrast(a: &Color, b: &Color, mapping: &ColorMapping) -> f32 {
+        a.resolve(mapping)
+            .unwrap()
+            .contrast_ratio(b.resolve(mapping).unwrap())
+    }
+
+    #[test]
+    fn random_colors_contrast_with_the_background() {
+        let mut rng = SmallRng::seed_from_u64(0);
+        let mapping = ColorMapping::default();
+
+        for background in [Color::White, Color::Black, Color::Brown] {
+            for _ in 0..100 {
+                let color = Color::random_contrasting(
+                    &mut rng,
+                    background.clone(),
+                    &mapping,
+                );
+                assert_ne!(color, background);
+                assert!(
+                    contrast(&color, &background, &mapping) >= MIN_CONTRAST,
+                    "{color:?} on {background:?}"
+                );
+            }
+        }
+
+        // gray on white is too pale to be picked
+        let picks: Vec<_> = (0..100)
+            .map(|_| Color::random_contrasting(&mut rng, Color::White, &mapping))
+            .collect();
+        assert!(!picks.contains(&Color::Gray));
+        assert!(!picks.contains(&Color::Yellow));
+    }
+
+    #[test]
+    fn most_contrasting_colors_when_none_is_enough() {
+        let mut rng = SmallRng::seed_from_u64(0);
+        // everything is a shade of gray, but black is still the darkest
+        let mapping = Color::all()
+            .into_iter()
+            .map(|color| (color.name(), "#777777".to_string()))
+            .collect::<ColorMapping>()
+            .with("black", "#555555");
+
+        for _ in 0..20 {
+            assert_eq!(
+                Color::random_contrasting(&mut rng, Color::White, &mapping),
+                Color::Black
+            );
+        }
+    }
+
+    #[test]
+    fn unresolved_colors_are_left_out() {
+        let mut rng = SmallRng::seed_from_u64(0);
+        let mapping = Color::all()
+            .into_iter()
+            .map(|color| (color.name(), "url(#gradient)".to_string()))
+            .collect::<ColorMapping>()
+            .with("white", "#ffffff")
+            .with("blue", "#0000ff");
+
+        for _ in 0..20 {
+            assert_eq!(
+                Color::random_contrasting(&mut rng, Color::White, &mapping),
+                Color::Blue
+            );
+            // a background that can't be resolved only rules itself out
+            assert_ne!(
+                Color::random_contrasting(&mut rng, Color::Red, &mapping),
+                Color::Red
+            );
+        }
+    }
+
+    #[test]
+    fn random_solid_fills() {
+        let mut rng = SmallRng::seed_from_u64(0);
+        let mapping = ColorMapping::default();
+        let white = Rgba::new(255, 255, 255);
+
+        for _ in 0..50 {
+            let Fill::Solid(color) =
+                Fill::random_solid(&mut rng, Some(Color::White), &mapping)
+            else {
+                panic!("random_solid should give a solid fill");
+            };
+            assert!(
+                color.resolve(&mapping).unwrap().contrast_ratio(white)
+                    >= MIN_CONTRAST
+            );
+        }
     }
 }
@@ -1,24 +1,27 @@
-use crate::{Color, Fill};
+use crate::{Color, ColorMapping, Fill};
 
 impl Fill {
+    /// Random color, contrasting with the background if there is one, see `Color::random_contrasting`
     pub fn random_solid<R: rand::Rng>(
         rng: &mut R,
-        except: Option<Color>,
+        background: Option<Color>,
+        colormap: &ColorMapping,
     ) -> Self {
-        Fill::Solid(match except {
-            Some(color) => Color::random_except(rng, color),
+        Fill::Solid(match background {
+            Some(color) => Color::random_contrasting(rng, color, colormap),
             None => rng.random(),
         })
     }
 
     pub fn random_hatches<R: rand::Rng>(
         rng: &mut R,
-        except: Option<Color>,
+        background: Option<Color>,
+        colormap: &ColorMapping,
     ) -> Self {
         let hatch_size = rng.random_range(5..=100) as f32 * 1e-2;
         Fill::Hatches(
-            match except {
-                Some(color) => Color::random_except(rng, color),
+            match background {
+                Some(color) => Color::random_contrasting(rng, color, colormap),
                 None => rng.random(),
             },
             rng.random(),
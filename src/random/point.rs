@@ -1,21 +1,20 @@
 use rand::Rng;
 
-use crate::{Point, Region};
+use crate::{
+    Point, Region,
+    geometry::region::{cell_center, cell_corner},
+};
 
 impl Point {
     pub fn random(rng: &mut impl Rng, within: &Region) -> Self {
         within.ensure_nonempty().unwrap();
-        Self::Corner(
-            rng.random_range(within.x_range()),
-            rng.random_range(within.y_range()),
-        )
+        let (x, y) = within.random_cell(rng);
+        cell_corner(x, y)
     }
 
     pub fn random_center(rng: &mut impl Rng, within: &Region) -> Self {
         within.ensure_nonempty().unwrap();
-        Self::Center(
-            rng.random_range(within.x_range()),
-            rng.random_range(within.y_range()),
-        )
+        let (x, y) = within.random_cell(rng);
+        cell_center(x, y)
     }
 }
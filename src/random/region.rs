@@ -1,7 +1,25 @@
-use crate::{Containable, Point, Region};
+use crate::{Containable, Point, Region, geometry::region::cell_corner};
 use rand::{Rng, distr::uniform::SampleRange, seq::IteratorRandom};
 
 impl Region {
+    /// Coordinates of one of the region's cells, which can be off-canvas, see `Region::cells`
+    pub(crate) fn random_cell(
+        &self,
+        rng: &mut (impl Rng + ?Sized),
+    ) -> (isize, isize) {
+        let ((start_x, start_y), (end_x, end_y)) = self.cells();
+        // isize can't be sampled, since its size depends on the platform.
+        // Cells on the grid are sampled as usize, so that seeded randomness gives the same results as before.
+        let mut sample = |start: isize, end: isize| {
+            if start >= 0 {
+                rng.random_range(start as usize..=end as usize) as isize
+            } else {
+                rng.random_range(start as i64..=end as i64) as isize
+            }
+        };
+        (sample(start_x, end_x), sample(start_y, end_y))
+    }
+
     pub fn random_end(&self, rng: &mut impl Rng, start: Point) -> Point {
         // End anchors are always a square diagonal from the start anchor (for now)
         // that means taking steps of the form n * (one of (1, 1), (1, -1), (-1, 1), (-1, -1))
@@ -35,7 +53,7 @@ impl Region {
     pub fn random(rng: &mut impl Rng, within: &Region) -> Self {
         let start = Point::random(rng, within);
         let end = within.random_end(rng, start);
-        Region::from(if start.x() > end.x() {
+        Region::from(if start.xy::<isize>().0 > end.xy::<isize>().0 {
             (end, start)
         } else {
             (start, end)
@@ -71,9 +89,7 @@ impl SampleRange<Point> for Region {
             return Err(rand::distr::uniform::Error::EmptyRange);
         }
 
-        Ok(Point::Corner(
-            rng.random_range(self.x_range()),
-            rng.random_range(self.y_range()),
-        ))
+        let (x, y) = self.random_cell(rng);
+        Ok(cell_corner(x, y))
     }
 }
@@ -1,8 +1,9 @@
 use super::renderable::SVGRenderable;
 use crate::{
-    Object,
+    CellSize,
     graphics::canvas::Canvas,
     rendering::{
+        fonts,
         rasterization::{
             create_pixmap, pixmap_to_png_data, svg_to_usvg_tree,
             usvg_tree_to_pixmap, write_png_data,
@@ -17,18 +18,33 @@ impl SVGRenderable for Canvas {
     fn render_to_svg(
         &self,
         colormap: crate::ColorMapping,
-        cell_size: usize,
+        cell_size: &CellSize,
         object_sizes: crate::graphics::objects::ObjectSizes,
         _id: &str,
+    ) -> anyhow::Result<svg::Node> {
+        fonts::with_fontdb(&self.fontdb, || {
+            self.render_svg_document(colormap, cell_size, object_sizes)
+        })
+    }
+}
+
+impl Canvas {
+    fn render_svg_document(
+        &self,
+        colormap: crate::ColorMapping,
+        cell_size: &CellSize,
+        object_sizes: crate::graphics::objects::ObjectSizes,
     ) -> anyhow::Result<svg::Node> {
         debug_time!("render_to_svg/canvas");
-        let background_color = self.background.unwrap_or_default();
+        let background_color = self.background.clone().unwrap_or_default();
+        background_color.ensure_renderable(&self.colormap)?;
+        let (left, top) = self.origin();
         let mut svg = svg::tag("svg").attr("xmlns", "http://www.w3.org/2000/svg");
 
         svg.add(
             svg::tag("rect")
-                .attr("x", -(self.outer_padding as i32))
-                .attr("y", -(self.outer_padding as i32))
+                .attr("x", left)
+                .attr("y", top)
                 .attr("width", self.width())
                 .attr("height", self.height())
                 .attr("fill", background_color.render(&self.colormap)),
@@ -61,21 +77,21 @@ impl SVGRenderable for Canvas {
             }
         }
 
-        for layer in self.layers.iter() {
-            for Object { clip_to, .. } in layer.objects.values() {
-                if let Some(region) = clip_to {
-                    defs.add(
-                        svg::tag("clipPath")
-                            .attr("id", region.clip_path_id())
-                            .child(
-                                svg::tag("rect")
-                                    .position(region.start, cell_size)
-                                    .size(*region, cell_size)
-                                    .node(),
-                            ),
-                    );
-                }
-            }
+        for clip in self.unique_clips() {
+            defs.add(clip.render_definition(
+                self,
+                colormap.clone(),
+                cell_size,
+                object_sizes,
+            )?);
+        }
+
+        for mask in self.unique_masks() {
+            defs.add(mask.render_definition(
+                self,
+                colormap.clone(),
+                cell_size,
+            )?);
         }
 
         svg.add(defs);
@@ -83,20 +99,13 @@ impl SVGRenderable for Canvas {
         Ok(svg
             .attr(
                 "viewBox",
-                format!(
-                    "{0} {0} {1} {2}",
-                    -(self.outer_padding as i32),
-                    self.width(),
-                    self.height()
-                ),
+                format!("{left} {top} {} {}", self.width(), self.height()),
             )
             .attr("width", self.width())
             .attr("height", self.height())
             .into())
     }
-}
 
-impl Canvas {
     pub fn svg_to_pixmap(
         &self,
         width: u32,
@@ -120,7 +129,7 @@ impl Canvas {
         let svg_contents = self
             .render_to_svg(
                 self.colormap.clone(),
-                self.cell_size,
+                &self.cell_size,
                 self.object_sizes,
                 "",
             )?
@@ -147,7 +156,7 @@ impl Canvas {
 
         let rendered = self.render_to_svg(
             self.colormap.clone(),
-            self.cell_size,
+            &self.cell_size,
             self.object_sizes,
             "",
         )?;
@@ -0,0 +1,163 @@
+use super::{renderable::SVGRenderable, svg};
+use crate::{Canvas, CellSize, Clip, Mask, MaskMode, Shape};
+use anyhow::{Result, anyhow};
+
+impl Clip {
+    /// Clips are rendered as masks painted in white, since `<clipPath>` only accepts basic shapes, and not groups.
+    pub(crate) fn render_definition(
+        &self,
+        canvas: &Canvas,
+        colormap: crate::ColorMapping,
+        cell_size: &CellSize,
+        object_sizes: crate::graphics::objects::ObjectSizes,
+    ) -> Result<svg::Node> {
+        let id = self.id();
+        let render = |shape: &Shape| {
+            shape.render_to_svg(colormap.clone(), cell_size, object_sizes, &id)
+        };
+
+        let contents: Vec<svg::Node> = match self {
+            Clip::Region(region) => {
+                vec![svg::cells(*region, cell_size).attr("fill", "white").into()]
+            }
+            Clip::Shape(shape) => {
+                vec![shape.render_painted("white", &id, &render, cell_size)?]
+            }
+            Clip::Layer(name) => canvas
+                .find_layer(name)
+                .ok_or_else(|| {
+                    anyhow!("Cannot clip to non-existent layer {name}")
+                })?
+                .walk()
+                .into_iter()
+                .flat_map(|layer| layer.objects().values())
+                .enumerate()
+                .map(|(i, object)| {
+                    object.shape.render_painted(
+                        "white",
+                        &format!("{id}-{i}"),
+                        &render,
+                        cell_size,
+                    )
+                })
+                .collect::<Result<_>>()?,
+        };
+
+        Ok(svg::tag("mask").attr("id", id).wrapping(contents).into())
+    }
+}
+
+impl Mask {
+    pub(crate) fn render_definition(
+        &self,
+        canvas: &Canvas,
+        colormap: crate::ColorMapping,
+        cell_size: &CellSize,
+    ) -> Result<svg::Node> {
+        let layer = canvas.find_layer(&self.layer).ok_or_else(|| {
+            anyhow!("Cannot mask with non-existent layer {}", self.layer)
+        })?;
+
+        let contents = layer.render_to_svg(
+            colormap,
+            cell_size,
+            layer.object_sizes,
+            &layer.name,
+        )?;
+
+        Ok(svg::tag("mask")
+            .attr("id", self.id())
+            .attr(
+                "mask-type",
+                match self.mode {
+                    MaskMode::Luminance => "luminance",
+                    MaskMode::Alpha => "alpha",
+                },
+            )
+            .child(contents)
+            .into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ColorMapping, ObjectSizes, Point, Region};
+
+    fn raw_square() -> Shape {
+        Shape::raw_svg(
+            r##"<rect width="2" height="2" fill="#c0ffee"/>"##,
+            "#c0ffee",
+        )
+        .unwrap()
+    }
+
+    fn render(clip: Clip, canvas: &Canvas) -> Result<String> {
+        clip.render_definition(
+            canvas,
+            ColorMapping::default(),
+            &CellSize::square(10),
+            ObjectSizes::default(),
+        )
+        .map(|node| node.to_string())
+    }
+
+    #[test]
+    fn raw_svg_clips_are_painted_white() {
+        let rendered =
+            render(Clip::Shape(raw_square()), &Canvas::new(4, 4)).unwrap();
+        assert!(rendered.contains("<mask"));
+        assert!(rendered.contains("white"));
+        assert!(!rendered.contains("#c0ffee"));
+    }
+
+    #[test]
+    fn layer_clips_include_raw_svg_objects() {
+        let mut canvas = Canvas::new(4, 4);
+        let layer = canvas.new_layer("clip");
+        layer.set("raw", raw_square());
+        layer.set(
+            "square",
+            Shape::Rectangle(Point::Corner(2, 2), Point::Corner(3, 3)),
+        );
+
+        let rendered = render(Clip::Layer("clip".into()), &canvas).unwrap();
+        assert!(
+            rendered.contains("<rect width=\"2\" height=\"2\" fill=\"white\"/>")
+        );
+        assert!(!rendered.contains("#c0ffee"));
+    }
+
+    #[test]
+    fn clips_to_missing_layers_fail() {
+        assert!(render(Clip::Layer("nope".into()), &Canvas::new(4, 4)).is_err());
+    }
+
+    #[test]
+    fn region_clip_ids_keep_negative_coordinates() {
+        let region = Region::covering((-2.0, -1.0), (1.0, 1.0));
+        assert_eq!(region.clip_path_id(), "clip--2--1-0-0");
+    }
+
+    #[test]
+    fn fractional_region_clip_ids_differ() {
+        let half = Region::new(Point::Fractional(0.5, 0.5), Point::Corner(1, 1))
+            .unwrap();
+        let whole =
+            Region::new(Point::Corner(0, 0), Point::Corner(1, 1)).unwrap();
+        assert_ne!(Clip::Region(half).id(), Clip::Region(whole).id());
+    }
+
+    #[test]
+    fn shape_clip_ids_follow_the_shape() {
+        let id = |shape: Shape| Clip::Shape(shape).id();
+        let line =
+            |width| Shape::Line(Point::Corner(0, 0), Point::Corner(2, 2), width);
+        assert_eq!(id(line(1.0)), id(line(1.0)));
+        assert_ne!(id(line(1.0)), id(line(1.5)));
+        assert_ne!(
+            id(Shape::Dot(Point::Corner(1, 1))),
+            id(Shape::BigDot(Point::Corner(1, 1)))
+        );
+    }
+}
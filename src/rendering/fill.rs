@@ -10,7 +10,10 @@ impl CSSRenderable for Fill {
             Fill::Translucent(color, opacity) => {
                 format!("fill: {}; opacity: {};", color.render(colormap), opacity)
             }
-            Fill::Dotted(..) | Fill::Hatches(..) => {
+            Fill::Dotted(..)
+            | Fill::Hatches(..)
+            | Fill::LinearGradient(..)
+            | Fill::RadialGradient(..) => {
                 format!("fill: url(#{});", self.pattern_id())
             }
         }
@@ -28,8 +31,12 @@ impl CSSRenderable for Fill {
                     opacity
                 )
             }
-            Fill::Dotted(..) => unimplemented!(),
-            Fill::Hatches(..) => unimplemented!(),
+            Fill::Dotted(..)
+            | Fill::Hatches(..)
+            | Fill::LinearGradient(..)
+            | Fill::RadialGradient(..) => {
+                format!("stroke: url(#{}); fill: transparent;", self.pattern_id())
+            }
         }
     }
 }
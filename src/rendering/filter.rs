@@ -1,6 +1,6 @@
 use measure_time::debug_time;
 
-use crate::{ColorMapping, Filter, FilterType};
+use crate::{CellSize, ColorMapping, Filter, FilterType};
 
 use super::{CSSRenderable, renderable::SVGRenderable, svg};
 
@@ -8,7 +8,7 @@ impl SVGRenderable for Filter {
     fn render_to_svg(
         &self,
         _colormap: crate::ColorMapping,
-        _cell_size: usize,
+        _cell_size: &CellSize,
         _object_sizes: crate::graphics::objects::ObjectSizes,
         _id: &str,
     ) -> anyhow::Result<svg::Node> {
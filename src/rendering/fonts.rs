@@ -1,9 +1,23 @@
 use itertools::Itertools;
 use resvg::usvg;
-use std::path::PathBuf;
+use std::{cell::RefCell, collections::HashMap, path::PathBuf, sync::Arc};
 
 use crate::Canvas;
 
+use super::{rasterization::svg_to_usvg_tree, svg};
+
+/// Font size at which text is measured. Widths at other sizes are derived from it, since they scale linearly.
+const REFERENCE_FONT_SIZE: f32 = 100.0;
+
+/// Approximate glyph width, as a fraction of the font size, used when no font database is available.
+const AVERAGE_GLYPH_WIDTH: f32 = 0.6;
+
+thread_local! {
+    /// Font database of the canvas that is currently being rendered on this thread, if any.
+    static RENDERING_FONTDB: RefCell<Option<Arc<usvg::fontdb::Database>>> =
+        const { RefCell::new(None) };
+}
+
 #[derive(Default, Debug, Clone)]
 pub struct FontOptions {
     pub skip_system_fonts: bool,
@@ -55,7 +69,7 @@ pub fn load_fonts(args: &FontOptions) -> anyhow::Result<usvg::Options<'_>> {
 }
 
 impl Canvas {
-    pub fn show_available_fonts(&self) -> () {
+    pub fn show_available_fonts(&self) {
         match self.fontdb {
             Some(ref fontdb) => println!(
                 "Available fonts: {:?}",
@@ -71,3 +85,177 @@ impl Canvas {
         };
     }
 }
+
+/// Runs `render` with `fontdb` available to shapes that need to measure text (see `Shape::FittedText`)
+pub(crate) fn with_fontdb<T>(
+    fontdb: &Option<Arc<usvg::fontdb::Database>>,
+    render: impl FnOnce() -> T,
+) -> T {
+    let previous = RENDERING_FONTDB.replace(fontdb.clone());
+    let result = render();
+    RENDERING_FONTDB.set(previous);
+    result
+}
+
+/// Measures the width, in pixels, of a single line of text, by laying it out with usvg.
+/// Returns None if no font of the database could be used to render the text.
+pub fn measure_text(
+    fontdb: &Arc<usvg::fontdb::Database>,
+    text: &str,
+    font_family: &str,
+    font_size: f32,
+) -> Option<f32> {
+    let document = svg::tag("svg")
+        .attr("xmlns", "http://www.w3.org/2000/svg")
+        .attr("width", 1)
+        .attr("height", 1)
+        .child(
+            svg::tag("text")
+                .attr("font-family", font_family)
+                .attr("font-size", font_size)
+                .child(svg::Node::Text(text.to_string())),
+        )
+        .node()
+        .to_string();
+
+    let tree = svg_to_usvg_tree(&document, &Some(fontdb.clone())).ok()?;
+    let width = tree.root().bounding_box().width();
+
+    (width > 0.0).then_some(width)
+}
+
+/// Result of fitting text into a box: lines to render, and the font size to render them at.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TextLayout {
+    pub font_size: f32,
+    pub lines: Vec<String>,
+}
+
+/// Finds the largest font size at which `content` fits in a box of the given size (in pixels), wrapping lines at word boundaries.
+/// Line breaks in `content` are preserved. `line_height` is relative to the font size.
+/// Uses the font database of the canvas being rendered to measure text, or a rough approximation if there is none.
+///
+/// ```
+/// use shapemaker::fonts::fit_text;
+///
+/// // a tall box: wrapping gives a bigger font size than a single line
+/// let layout = fit_text("one two", "Inconsolata", (100.0, 1000.0), 1.2);
+/// assert_eq!(layout.lines, vec!["one", "two"]);
+/// ```
+pub fn fit_text(
+    content: &str,
+    font_family: &str,
+    (width, height): (f32, f32),
+    line_height: f32,
+) -> TextLayout {
+    let fontdb = RENDERING_FONTDB.with_borrow(|fontdb| fontdb.clone());
+    let mut measurements = HashMap::<String, f32>::new();
+    let mut measure = |line: &str| {
+        *measurements.entry(line.to_string()).or_insert_with(|| {
+            fontdb
+                .as_ref()
+                .and_then(|fontdb| {
+                    measure_text(fontdb, line, font_family, REFERENCE_FONT_SIZE)
+                })
+                .unwrap_or(
+                    line.chars().count() as f32
+                        * AVERAGE_GLYPH_WIDTH
+                        * REFERENCE_FONT_SIZE,
+                )
+        })
+    };
+
+    let mut wrap = |font_size: f32| -> Option<Vec<String>> {
+        let max_width = width * REFERENCE_FONT_SIZE / font_size;
+        let mut lines = vec![];
+        for paragraph in content.lines() {
+            let mut current = String::new();
+            for word in paragraph.split_whitespace() {
+                let candidate = if current.is_empty() {
+                    word.to_string()
+                } else {
+                    format!("{current} {word}")
+                };
+
+                if measure(&candidate) <= max_width {
+                    current = candidate;
+                } else if current.is_empty() {
+                    // word is too long to fit on a line by itself
+                    return None;
+                } else {
+                    lines.push(current);
+                    if measure(word) > max_width {
+                        return None;
+                    }
+                    current = word.to_string();
+                }
+            }
+            lines.push(current);
+        }
+        Some(lines)
+    };
+
+    let fits = |lines: &Vec<String>, font_size: f32| {
+        lines.len() as f32 * line_height * font_size <= height
+    };
+
+    // Binary search on the font size, the largest possible one being a single line filling the whole height
+    let (mut smallest, mut largest) = (0.0, height / line_height);
+    let mut layout = TextLayout {
+        font_size: 0.0,
+        lines: vec![],
+    };
+    for _ in 0..24 {
+        let font_size = (smallest + largest) / 2.0;
+        match wrap(font_size) {
+            Some(lines) if fits(&lines, font_size) => {
+                smallest = font_size;
+                layout = TextLayout { font_size, lines };
+            }
+            _ => largest = font_size,
+        }
+    }
+
+    layout
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Width of a line of text with the approximation used without a font database
+    fn approximate_width(line: &str, font_size: f32) -> f32 {
+        line.chars().count() as f32 * AVERAGE_GLYPH_WIDTH * font_size
+    }
+
+    #[test]
+    fn fitted_text_stays_in_its_box() {
+        let (width, height) = (300.0, 200.0);
+        let layout = fit_text(
+            "the quick brown fox jumps",
+            "Inconsolata",
+            (width, height),
+            1.2,
+        );
+
+        assert!(layout.font_size > 0.0);
+        assert!(layout.lines.len() as f32 * 1.2 * layout.font_size <= height);
+        for line in &layout.lines {
+            assert!(approximate_width(line, layout.font_size) <= width + 1e-3);
+        }
+    }
+
+    #[test]
+    fn fitted_text_keeps_line_breaks() {
+        let layout = fit_text("a\nb", "Inconsolata", (1000.0, 100.0), 1.0);
+        assert_eq!(layout.lines, vec!["a", "b"]);
+    }
+
+    #[test]
+    fn wide_boxes_fit_text_on_one_line() {
+        let layout = fit_text("one two", "Inconsolata", (1000.0, 50.0), 1.0);
+        assert_eq!(layout.lines, vec!["one two"]);
+        // limited by the height
+        assert!((layout.font_size - 50.0).abs() < 0.1);
+    }
+}
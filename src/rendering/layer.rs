@@ -1,22 +1,79 @@
 use itertools::Itertools;
 use measure_time::debug_time;
 
-use super::{renderable::SVGRenderable, svg};
-use crate::Layer;
+use super::{
+    renderable::{SVGAttributesRenderable, SVGRenderable},
+    svg,
+};
+use crate::{BlendMode, CellSize, Layer};
+
+impl BlendMode {
+    pub fn css_name(&self) -> &'static str {
+        match self {
+            BlendMode::Normal => "normal",
+            BlendMode::Multiply => "multiply",
+            BlendMode::Screen => "screen",
+            BlendMode::Overlay => "overlay",
+            BlendMode::Darken => "darken",
+            BlendMode::Lighten => "lighten",
+            BlendMode::ColorDodge => "color-dodge",
+            BlendMode::ColorBurn => "color-burn",
+            BlendMode::HardLight => "hard-light",
+            BlendMode::SoftLight => "soft-light",
+            BlendMode::Difference => "difference",
+            BlendMode::Exclusion => "exclusion",
+            BlendMode::Hue => "hue",
+            BlendMode::Saturation => "saturation",
+            BlendMode::Color => "color",
+            BlendMode::Luminosity => "luminosity",
+        }
+    }
+}
 
 impl SVGRenderable for Layer {
     fn render_to_svg(
         &self,
         colormap: crate::ColorMapping,
-        cell_size: usize,
+        cell_size: &CellSize,
         object_sizes: crate::graphics::objects::ObjectSizes,
         id: &str,
     ) -> anyhow::Result<svg::Node> {
         debug_time!("render_to_svg/layer");
         let mut group = svg::tag("g").class("layer").dataset("layer", &self.name);
-        for (object_id, object) in
-            self.objects.iter().sorted_by_key(|(oid, _)| (*oid).clone())
-        {
+
+        if self.opacity != 1.0 {
+            group = group.style("opacity", self.opacity);
+        }
+
+        if self.blend_mode != BlendMode::Normal {
+            group = group.style("mix-blend-mode", self.blend_mode.css_name());
+        }
+
+        if !self.transformations.is_empty() {
+            let (center_x, center_y) = self.region().center_coords(cell_size);
+            for (key, value) in self.transformations.render_to_svg_attributes(
+                colormap.clone(),
+                cell_size,
+                object_sizes,
+                id,
+            )? {
+                group = group.attr(&key, value);
+            }
+            group = group
+                .style("transform-origin", format!("{center_x}px {center_y}px"));
+        }
+
+        if !self.filters.is_empty() {
+            group = group.style(
+                "filter",
+                self.filters
+                    .iter()
+                    .map(|filter| format!("url(#{})", filter.id()))
+                    .join(" "),
+            );
+        }
+
+        for (object_id, object) in self.objects_sorted() {
             group.add(object.render_to_svg(
                 colormap.clone(),
                 cell_size,
@@ -24,6 +81,116 @@ impl SVGRenderable for Layer {
                 &format!("{}--{}", id, object_id),
             )?);
         }
-        Ok(group.into())
+
+        for nested in self.layers.iter().filter(|group| !group.hidden).rev() {
+            group.add(nested.render_to_svg(
+                colormap.clone(),
+                cell_size,
+                nested.object_sizes,
+                &format!("{}/{}", id, nested.name),
+            )?);
+        }
+
+        // clipping is applied outside of the layer's transformations
+        Ok(match &self.clip_to {
+            Some(clip) => svg::tag("g")
+                .attr("mask", format!("url(#{})", clip.id()))
+                .child(group)
+                .into(),
+            None => group.into(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        Color, ColorMapping, Fill, Filter, ObjectSizes, Point, Shape,
+        Transformation,
+    };
+
+    fn render(layer: &Layer) -> String {
+        layer
+            .render_to_svg(
+                ColorMapping::default(),
+                &CellSize::square(10),
+                ObjectSizes::default(),
+                &layer.name,
+            )
+            .unwrap()
+            .to_string()
+    }
+
+    fn layer_with_a_square() -> Layer {
+        let mut layer = Layer::new("layer");
+        layer.set(
+            "square",
+            Shape::Rectangle(Point::Corner(0, 0), Point::Corner(1, 1)),
+        );
+        layer
+    }
+
+    #[test]
+    fn plain_layers_have_no_styles() {
+        let rendered = render(&layer_with_a_square());
+        assert!(!rendered.contains("opacity"));
+        assert!(!rendered.contains("mix-blend-mode"));
+        assert!(!rendered.contains("filter"));
+    }
+
+    #[test]
+    fn layer_opacity_and_blend_mode() {
+        let mut layer = layer_with_a_square();
+        layer.set_opacity(0.5);
+        layer.set_blend_mode(BlendMode::ColorDodge);
+
+        let rendered = render(&layer);
+        assert!(rendered.contains("opacity: 0.5"));
+        assert!(rendered.contains("mix-blend-mode: color-dodge"));
+    }
+
+    #[test]
+    fn layer_filters_and_transformations_apply_to_the_group() {
+        let mut layer = layer_with_a_square();
+        let glow = Filter::glow(2.0);
+        layer.filter(glow);
+        layer.transform(Transformation::Rotate(45.0));
+
+        let rendered = render(&layer);
+        assert!(rendered.contains(&format!("url(#{})", glow.id())));
+        assert!(rendered.contains("rotate(45"));
+        assert!(rendered.contains("transform-origin"));
+
+        layer.clear_filters();
+        layer.clear_transformations();
+        let rendered = render(&layer);
+        assert!(!rendered.contains("filter"));
+        assert!(!rendered.contains("rotate"));
+    }
+
+    #[test]
+    fn painting_all_objects() {
+        let mut layer = layer_with_a_square();
+        layer.paint_all_objects(Fill::Solid(Color::Red));
+        assert!(render(&layer).contains("fill: red;"));
+    }
+
+    #[test]
+    fn swatches_missing_from_the_palette_fail() {
+        let mut layer = layer_with_a_square();
+        layer.paint_all_objects(Fill::Solid(Color::named("teal")));
+        assert!(render(&layer).contains("teal"));
+
+        layer.paint_all_objects(Fill::Solid(Color::named("gren")));
+        let error = layer
+            .render_to_svg(
+                ColorMapping::default(),
+                &CellSize::square(10),
+                ObjectSizes::default(),
+                &layer.name,
+            )
+            .unwrap_err();
+        assert!(error.to_string().contains("gren"));
     }
 }
@@ -1,4 +1,5 @@
 pub mod canvas;
+pub mod clip;
 pub mod fill;
 pub mod filter;
 pub mod fonts;
@@ -7,6 +8,7 @@ pub mod objects;
 pub mod rasterization;
 pub mod renderable;
 pub mod shapes;
+pub mod stroke;
 pub mod svg;
 pub mod transform;
 
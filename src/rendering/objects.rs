@@ -1,7 +1,7 @@
 use itertools::Itertools;
 use measure_time::debug_time;
 
-use crate::{Fill, Object, Shape};
+use crate::{CellSize, Fill, Object, Shape};
 
 use super::{
     CSSRenderable, SVGAttributesRenderable, renderable::SVGRenderable, svg,
@@ -11,14 +11,23 @@ impl SVGRenderable for Object {
     fn render_to_svg(
         &self,
         colormap: crate::ColorMapping,
-        cell_size: usize,
+        cell_size: &CellSize,
         object_sizes: crate::graphics::objects::ObjectSizes,
         id: &str,
     ) -> anyhow::Result<svg::Node> {
         debug_time!("render_to_svg/colored_object");
 
+        for color in self
+            .fill
+            .iter()
+            .chain(self.stroke.as_ref().map(|stroke| &stroke.fill))
+            .flat_map(Fill::colors)
+        {
+            color.ensure_renderable(&colormap)?;
+        }
+
         let plain_obj = match &self.shape {
-            Shape::RawSVG { .. } => self.render_raw_svg(&colormap),
+            Shape::RawSVG { .. } => self.render_raw_svg(&colormap, cell_size, id),
             _ => self.shape.render_to_svg(
                 colormap.clone(),
                 cell_size,
@@ -31,6 +40,15 @@ impl SVGRenderable for Object {
             .fill
             .render_to_css(&colormap.clone(), !self.shape.fillable());
 
+        if let Some(stroke) = &self.stroke {
+            // Objects with only an outline should not get SVG's default black fill
+            if self.fill.is_none() {
+                css += "fill: transparent;";
+            }
+            css += " ";
+            css += &stroke.render_to_css_filled(&colormap);
+        }
+
         let object_svg = if !self.transformations.is_empty()
             || !self.filters.is_empty()
         {
@@ -67,30 +85,39 @@ impl SVGRenderable for Object {
             }
         };
 
-        if let Some(region) = &self.clip_to {
-            Ok(svg::tag("g")
-                .attr("clip-path", region.clip_path_id())
+        let object_svg = match &self.mask {
+            Some(mask) => svg::tag("g")
+                .attr("mask", format!("url(#{})", mask.id()))
                 .child(object_svg)
-                .into())
-        } else {
-            Ok(object_svg)
-        }
+                .into(),
+            None => object_svg,
+        };
+
+        Ok(match &self.clip_to {
+            Some(clip) => svg::tag("g")
+                .attr("mask", format!("url(#{})", clip.id()))
+                .child(object_svg)
+                .into(),
+            None => object_svg,
+        })
     }
 }
 
 impl Object {
-    fn render_raw_svg(&self, colormap: &crate::ColorMapping) -> svg::Node {
-        if let Shape::RawSVG { content, color } = &self.shape {
-            let filled_svg = match &self.fill {
-                Some(Fill::Solid(fill_color)) => {
-                    content.replace(color, &fill_color.render(colormap))
-                }
-                _ => content.clone(),
-            };
-
-            svg::Node::SVG(filled_svg)
-        } else {
-            panic!("Called render_raw_svg on a non-RawSVG shape");
-        }
+    fn render_raw_svg(
+        &self,
+        colormap: &crate::ColorMapping,
+        cell_size: &CellSize,
+        id: &str,
+    ) -> svg::Node {
+        let fill = match &self.fill {
+            Some(Fill::Solid(fill_color)) => Some(fill_color.render(colormap)),
+            _ => None,
+        };
+
+        self.shape
+            .render_raw_svg(cell_size, fill.as_deref())
+            .dataset("object", id)
+            .into()
     }
 }
@@ -59,6 +59,25 @@ pub fn svg_to_usvg_tree(
     )?)
 }
 
+/// Computes the bounding box of some SVG content, as (min, max) corners, in the content's own units.
+/// The content can be a whole `<svg>` document or a fragment.
+pub fn svg_bounding_box(content: &str) -> Result<((f32, f32), (f32, f32))> {
+    let content = match content.trim_start().strip_prefix("<?xml") {
+        Some(rest) => rest.split_once("?>").map(|(_, c)| c).unwrap_or(rest),
+        None => content,
+    };
+
+    let tree = svg_to_usvg_tree(
+        &format!(
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="1" height="1">{content}</svg>"#
+        ),
+        &None,
+    )?;
+
+    let bbox = tree.root().abs_bounding_box();
+    Ok(((bbox.left(), bbox.top()), (bbox.right(), bbox.bottom())))
+}
+
 pub fn pixmap_to_png_data(pixmap: tiny_skia::Pixmap) -> anyhow::Result<Vec<u8>> {
     debug_time!("pixmap_to_png_data");
     Ok(pixmap.encode_png()?)
@@ -1,5 +1,5 @@
 use super::svg;
-use crate::{ColorMapping, graphics::objects::ObjectSizes};
+use crate::{CellSize, ColorMapping, graphics::objects::ObjectSizes};
 use anyhow::Result;
 use itertools::Itertools;
 use std::collections::HashMap;
@@ -9,7 +9,7 @@ pub trait SVGRenderable {
     fn render_to_svg(
         &self,
         colormap: ColorMapping,
-        cell_size: usize,
+        cell_size: &CellSize,
         object_sizes: ObjectSizes,
         id: &str,
     ) -> Result<svg::Node>;
@@ -23,7 +23,7 @@ pub trait SVGAttributesRenderable {
     fn render_to_svg_attributes(
         &self,
         colormap: ColorMapping,
-        cell_size: usize,
+        cell_size: &CellSize,
         object_sizes: ObjectSizes,
         id: &str,
     ) -> Result<HashMap<String, String>>;
@@ -75,7 +75,7 @@ where
     fn render_to_svg_attributes(
         &self,
         colormap: ColorMapping,
-        cell_size: usize,
+        cell_size: &CellSize,
         object_sizes: ObjectSizes,
         id: &str,
     ) -> Result<HashMap<String, String>> {
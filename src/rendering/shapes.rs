@@ -1,14 +1,29 @@
 use measure_time::debug_time;
+use slug::slugify;
 
-use crate::{LineSegment, Shape, graphics::objects::ObjectSizes};
+use crate::{
+    Anchor, Angle, CellSize, PathSegment, Region, Shape,
+    graphics::{
+        objects::ObjectSizes,
+        shapes::{
+            BooleanOperation, HorizontalAlignment, VerticalAlignment,
+            curve_control_point, point_on_circle,
+        },
+    },
+};
 
-use super::{renderable::SVGRenderable, svg};
+use super::{fonts::fit_text, renderable::SVGRenderable, svg};
+
+const FONT_FAMILY: &str = "Inconsolata";
+
+/// Line height of multi-line text, relative to the font size
+const LINE_HEIGHT: f32 = 1.2;
 
 impl SVGRenderable for Shape {
     fn render_to_svg(
         &self,
         colormap: crate::ColorMapping,
-        cell_size: usize,
+        cell_size: &CellSize,
         object_sizes: crate::graphics::objects::ObjectSizes,
         id: &str,
     ) -> anyhow::Result<svg::Node> {
@@ -17,8 +32,10 @@ impl SVGRenderable for Shape {
             Shape::Text(..) | Shape::CenteredText(..) => {
                 self.render_text(cell_size)
             }
+            Shape::FittedText { .. } => self.render_fitted_text(cell_size),
             Shape::Rectangle(..) => self.render_rectangle(cell_size),
             Shape::Polygon(..) => self.render_polygon(cell_size),
+            Shape::Path(..) => self.render_path(cell_size),
             Shape::Line(..) => self.render_line(cell_size),
             Shape::CurveInward(..) | Shape::CurveOutward(..) => {
                 self.render_curve(cell_size)
@@ -29,13 +46,20 @@ impl SVGRenderable for Shape {
             | Shape::SmallCircle(..) => {
                 self.render_circle(cell_size, object_sizes)
             }
+            Shape::Ellipse(..) => self.render_ellipse(cell_size),
+            Shape::Arc { .. } => self.render_arc(cell_size, object_sizes),
+            Shape::Sector { .. } => self.render_sector(cell_size),
             Shape::Image(..) => self.render_image(cell_size),
             Shape::Component { .. } => {
-                self.render_component(colormap, cell_size, object_sizes, id)
+                self.render_component(colormap, cell_size, object_sizes, id)?
             }
-            Shape::RawSVG { .. } => {
-                unimplemented!("RawSVG shapes require an Object to render")
+            Shape::Boolean { .. } => {
+                self.render_boolean(colormap, cell_size, object_sizes, id)?
             }
+            Shape::Tiling(..) => {
+                self.render_tiling(colormap, cell_size, object_sizes, id)?
+            }
+            Shape::RawSVG { .. } => self.render_raw_svg(cell_size, None).into(),
         };
 
         Ok(match rendered {
@@ -60,12 +84,10 @@ impl SVGRenderable for Shape {
 }
 
 impl Shape {
-    fn render_image(&self, cell_size: usize) -> svg::Node {
+    fn render_image(&self, cell_size: &CellSize) -> svg::Node {
         if let Shape::Image(region, path) = self {
             return svg::tag("image")
-                .coords(region.start.coords(cell_size))
-                .attr("width", region.width() * cell_size)
-                .attr("height", region.height() * cell_size)
+                .region(*region, cell_size)
                 .attr("href", path.clone())
                 .into();
         }
@@ -73,13 +95,43 @@ impl Shape {
         panic!("Expected Image, got {:?}", self);
     }
 
+    /// Renders the content of a `RawSVG` shape, scaled from grid units and moved to its position.
+    /// `paint` replaces the shape's `color` in the content, see `Object`.
+    pub(crate) fn render_raw_svg(
+        &self,
+        cell_size: &CellSize,
+        paint: Option<&str>,
+    ) -> svg::Element {
+        if let Shape::RawSVG {
+            content, color, at, ..
+        } = self
+        {
+            let content = match paint {
+                Some(paint) => content.replace(color, paint),
+                None => content.clone(),
+            };
+
+            let (x, y) = at.coords(cell_size);
+            let (width, height) = cell_size.cell(at.grid_coords());
+
+            return svg::tag("g")
+                .attr(
+                    "transform",
+                    format!("translate({x} {y}) scale({width} {height})"),
+                )
+                .child(svg::Node::SVG(content));
+        }
+
+        panic!("Expected RawSVG, got {:?}", self);
+    }
+
     fn render_component(
         &self,
         colormap: crate::ColorMapping,
-        cell_size: usize,
+        cell_size: &CellSize,
         object_sizes: crate::graphics::objects::ObjectSizes,
         id: &str,
-    ) -> svg::Node {
+    ) -> anyhow::Result<svg::Node> {
         if let Shape::Component {
             at,
             size: (real_w, real_h),
@@ -96,7 +148,14 @@ impl Shape {
             let percent =
                 |num: usize, den: usize| format!("{}%", (num * 100) / (den));
 
-            return svg::tag("svg")
+            let rendered = objects
+                .iter()
+                .map(|o| {
+                    o.render_to_svg(colormap.clone(), cell_size, object_sizes, id)
+                })
+                .collect::<anyhow::Result<Vec<_>>>()?;
+
+            return Ok(svg::tag("svg")
                 .coords(at.coords(cell_size))
                 .dataset("nested-dims", format!("{object_w}x{object_h}"))
                 .style(
@@ -107,17 +166,245 @@ impl Shape {
                         percent(*real_h, object_h)
                     ),
                 )
-                .wrapping(objects.iter().map(move |o| {
-                    o.render_to_svg(colormap.clone(), cell_size, object_sizes, id)
-                        .expect("Could not render component object to SVG")
-                }))
-                .into();
+                .wrapping(rendered)
+                .into());
         }
 
         panic!("Expected Component, got {:?}", self);
     }
 
-    fn render_text(&self, cell_size: usize) -> svg::Node {
+    fn render_boolean(
+        &self,
+        colormap: crate::ColorMapping,
+        cell_size: &CellSize,
+        object_sizes: crate::graphics::objects::ObjectSizes,
+        id: &str,
+    ) -> anyhow::Result<svg::Node> {
+        if let Shape::Boolean { .. } = self {
+            let render = |shape: &Shape| {
+                shape.render_to_svg(colormap.clone(), cell_size, object_sizes, id)
+            };
+
+            return self.render_masked(
+                &format!("boolean-{}", slugify(id)),
+                None,
+                &render,
+                cell_size,
+            );
+        }
+
+        panic!("Expected Boolean, got {:?}", self);
+    }
+
+    /// Renders the shape in a single color, ignoring its object's fill, for use inside masks.
+    /// `mask_id` is used as a prefix for the ids of masks needed by `Boolean` shapes.
+    pub(crate) fn render_painted(
+        &self,
+        paint: &str,
+        mask_id: &str,
+        render: &dyn Fn(&Shape) -> anyhow::Result<svg::Node>,
+        cell_size: &CellSize,
+    ) -> anyhow::Result<svg::Node> {
+        Ok(match self {
+            Shape::Boolean { .. } => {
+                self.render_masked(mask_id, Some(paint), render, cell_size)?
+            }
+            // only the parts of the content in the shape's color get painted
+            Shape::RawSVG { .. } => {
+                self.render_raw_svg(cell_size, Some(paint)).into()
+            }
+            _ if self.fillable() => svg::tag("g")
+                .attr("fill", paint)
+                .attr("stroke", "none")
+                .child(render(self)?)
+                .into(),
+            _ => svg::tag("g")
+                .attr("fill", "none")
+                .attr("stroke", paint)
+                .child(render(self)?)
+                .into(),
+        })
+    }
+
+    /// Paints the area of a `Boolean` shape through a luminance mask built from its operands.
+    /// `paint` overrides the fill, for booleans nested in another one's mask.
+    fn render_masked(
+        &self,
+        mask_id: &str,
+        paint: Option<&str>,
+        render: &dyn Fn(&Shape) -> anyhow::Result<svg::Node>,
+        cell_size: &CellSize,
+    ) -> anyhow::Result<svg::Node> {
+        let Shape::Boolean { op, a, b } = self else {
+            panic!("Expected Boolean, got {:?}", self);
+        };
+
+        // operands are painted in white to show them, and in black to cut them out
+        let operand = |shape: &Shape, paint: &str, suffix: &str| {
+            shape.render_painted(
+                paint,
+                &format!("{mask_id}-{suffix}"),
+                render,
+                cell_size,
+            )
+        };
+
+        let within_b_id = format!("{mask_id}-within-b");
+        let within_b = |node: svg::Node| -> svg::Node {
+            svg::tag("g")
+                .attr("mask", format!("url(#{within_b_id})"))
+                .child(node)
+                .into()
+        };
+
+        let mask = match op {
+            BooleanOperation::Union => {
+                vec![operand(a, "white", "a")?, operand(b, "white", "b")?]
+            }
+            BooleanOperation::Difference => {
+                vec![operand(a, "white", "a")?, operand(b, "black", "b")?]
+            }
+            BooleanOperation::Intersection => {
+                vec![within_b(operand(a, "white", "a")?)]
+            }
+            BooleanOperation::Xor => vec![
+                operand(a, "white", "a")?,
+                operand(b, "white", "b")?,
+                within_b(operand(a, "black", "a-cut")?),
+            ],
+        };
+
+        let mut masks = vec![svg::tag("mask").attr("id", mask_id).wrapping(mask)];
+        if matches!(op, BooleanOperation::Intersection | BooleanOperation::Xor) {
+            masks.push(
+                svg::tag("mask")
+                    .attr("id", &within_b_id)
+                    .child(operand(b, "white", "within-b")?),
+            );
+        }
+
+        // operands can be drawn slightly outside of their region, e.g. rectangles
+        let region = self.region();
+        let (x, y) = region.start.grid_coords();
+        let (width, height) = region.size(1);
+        let area = Region::covering(
+            (x - 1.0, y - 1.0),
+            (x + width + 1.0, y + height + 1.0),
+        );
+
+        let mut painted = svg::tag("rect")
+            .region(area, cell_size)
+            .attr("mask", format!("url(#{mask_id})"))
+            .attr("stroke", "none");
+        if let Some(paint) = paint {
+            painted = painted.attr("fill", paint);
+        }
+
+        Ok(svg::tag("g")
+            .wrapping([svg::tag("defs").wrapping(masks).node(), painted.node()])
+            .into())
+    }
+
+    fn render_tiling(
+        &self,
+        colormap: crate::ColorMapping,
+        cell_size: &CellSize,
+        object_sizes: crate::graphics::objects::ObjectSizes,
+        id: &str,
+    ) -> anyhow::Result<svg::Node> {
+        if let Shape::Tiling(region, tile, options) = self {
+            // tiles repeat with the size of the cell the tiling starts in
+            let (cell_width, cell_height) =
+                cell_size.cell(region.start.grid_coords());
+            let (step_x, step_y) = options
+                .step
+                .map(|(x, y)| (x * cell_width, y * cell_height))
+                .unwrap_or(tile.region().size(cell_size));
+            let (pattern_x, pattern_y) = region.start.coords(cell_size);
+            let tile_id = format!("tile-{}", slugify(id));
+            let pattern_id = format!("tiling-{}", slugify(id));
+
+            // Render the tile at the origin of the pattern
+            let mut tile = tile.as_ref().clone();
+            tile.shape.teleport(0, 0);
+
+            // The pattern covers 2×2 tiles, so that every other row can be staggered, and every other row or column mirrored.
+            // The top-left tile is the tile's definition itself.
+            let (pattern_width, pattern_height) = (2.0 * step_x, 2.0 * step_y);
+            let mut uses = vec![];
+            for (column, row) in [(1, 0), (0, 1), (1, 1)] {
+                let stagger = if row == 1 {
+                    options.stagger * cell_width
+                } else {
+                    0.0
+                };
+                let x =
+                    (column as f32 * step_x + stagger).rem_euclid(pattern_width);
+                let y = row as f32 * step_y;
+                let (flip_x, flip_y) = (
+                    options.mirror.0 && column == 1,
+                    options.mirror.1 && row == 1,
+                );
+
+                // Staggered tiles can overflow the pattern on the right: draw them again on the left
+                let copies = if x + step_x > pattern_width {
+                    vec![x, x - pattern_width]
+                } else {
+                    vec![x]
+                };
+
+                for x in copies {
+                    uses.push(
+                        svg::tag("use").attr("href", format!("#{tile_id}")).attr(
+                            "transform",
+                            format!(
+                                "translate({} {}) scale({} {})",
+                                if flip_x { x + step_x } else { x },
+                                if flip_y { y + step_y } else { y },
+                                if flip_x { -1 } else { 1 },
+                                if flip_y { -1 } else { 1 },
+                            ),
+                        ),
+                    );
+                }
+            }
+
+            let pattern = svg::tag("pattern")
+                .attr("id", &pattern_id)
+                .attr("patternUnits", "userSpaceOnUse")
+                .attr("x", pattern_x + options.offset.0 * cell_width)
+                .attr("y", pattern_y + options.offset.1 * cell_height)
+                .attr("width", pattern_width)
+                .attr("height", pattern_height)
+                .wrapping(
+                    std::iter::once(
+                        svg::tag("g")
+                            .attr("id", &tile_id)
+                            .child(tile.render_to_svg(
+                                colormap,
+                                cell_size,
+                                object_sizes,
+                                &tile_id,
+                            )?)
+                            .node(),
+                    )
+                    .chain(uses.into_iter().map(|u| u.node())),
+                );
+
+            return Ok(svg::tag("g")
+                .wrapping(vec![
+                    svg::tag("defs").child(pattern),
+                    svg::tag("rect")
+                        .region(*region, cell_size)
+                        .attr("fill", format!("url(#{pattern_id})")),
+                ])
+                .into());
+        }
+
+        panic!("Expected Tiling, got {:?}", self);
+    }
+
+    fn render_text(&self, cell_size: &CellSize) -> svg::Node {
         match self {
             Shape::Text(position, content, font_size)
             | Shape::CenteredText(position, content, font_size) => {
@@ -133,7 +420,7 @@ impl Shape {
                         .coords(cell_size),
                     )
                     .attr("font-size", format!("{}pt", font_size))
-                    .attr("font-family", "Inconsolata")
+                    .attr("font-family", FONT_FAMILY)
                     .attr(
                         "dominant-baseline",
                         if centered { "middle" } else { "hanging" },
@@ -149,45 +436,79 @@ impl Shape {
         }
     }
 
-    // fn render_fitted_text(&self, cell_size: usize) -> svg::Node {
-    //     if let Object::FittedText(region, content) = self {
-    //         let (x, y) = region.start.coords(cell_size);
-    //         let width = region.width() * cell_size as f32;
-    //         let height = region.height() * cell_size as f32;
-
-    //         return Box::new(
-    //             svg::node::element::Text::new(content.clone())
-    //                 .set("x", x)
-    //                 .set("y", y)
-    //                 .set("")
-    //                 .set("font-size", format!("{}pt", 10.0))
-    //                 .set("font-family", "sans-serif"),
-    //         );
-    //     }
-
-    //     panic!("Expected FittedText, got {:?}", self);
-    // }
-
-    fn render_rectangle(&self, cell_size: usize) -> svg::Node {
+    fn render_fitted_text(&self, cell_size: &CellSize) -> svg::Node {
+        if let Shape::FittedText {
+            region,
+            content,
+            horizontal,
+            vertical,
+        } = self
+        {
+            let (width, height) = region.size(cell_size);
+            let (left, top) = region.start.coords(cell_size);
+            let layout =
+                fit_text(content, FONT_FAMILY, (width, height), LINE_HEIGHT);
+            let line_height = layout.font_size * LINE_HEIGHT;
+
+            let (x, text_anchor) = match horizontal {
+                HorizontalAlignment::Left => (left, "start"),
+                HorizontalAlignment::Center => (left + width / 2.0, "middle"),
+                HorizontalAlignment::Right => (left + width, "end"),
+            };
+
+            let free_height = height - layout.lines.len() as f32 * line_height;
+            let first_line_top = top
+                + match vertical {
+                    VerticalAlignment::Top => 0.0,
+                    VerticalAlignment::Middle => free_height / 2.0,
+                    VerticalAlignment::Bottom => free_height,
+                };
+
+            return svg::tag("text")
+                .attr("font-size", layout.font_size)
+                .attr("font-family", FONT_FAMILY)
+                .attr("dominant-baseline", "middle")
+                .attr("text-anchor", text_anchor)
+                .wrapping(layout.lines.into_iter().enumerate().map(
+                    |(i, line)| {
+                        svg::tag("tspan")
+                            .attr("x", x)
+                            .attr(
+                                "y",
+                                first_line_top + (i as f32 + 0.5) * line_height,
+                            )
+                            .child(svg::Node::Text(line))
+                    },
+                ))
+                .into();
+        }
+
+        panic!("Expected FittedText, got {:?}", self);
+    }
+
+    fn render_rectangle(&self, cell_size: &CellSize) -> svg::Node {
         if let Shape::Rectangle(start, end) = self {
-            return svg::tag("rect").region((start, end), cell_size).into();
+            return svg::cells((start, end), cell_size).into();
         }
 
         panic!("Expected Rectangle, got {:?}", self);
     }
 
-    fn render_polygon(&self, cell_size: usize) -> svg::Node {
+    fn render_polygon(&self, cell_size: &CellSize) -> svg::Node {
         if let Shape::Polygon(start, lines) = self {
             let mut path = svg::Path::new();
             path.move_to(*start, cell_size);
+            let mut from = *start;
             for line in lines {
-                match line {
-                    LineSegment::Straight(end)
-                    | LineSegment::InwardCurve(end)
-                    | LineSegment::OutwardCurve(end) => {
-                        path.line_to(*end, cell_size);
-                    }
+                match line.control_point(from) {
+                    Some(control) => path.quadratic_curve_to(
+                        cell_size.coords(control),
+                        line.end(),
+                        cell_size,
+                    ),
+                    None => path.line_to(line.end(), cell_size),
                 };
+                from = line.end();
             }
             path.close();
             return path.node();
@@ -196,7 +517,38 @@ impl Shape {
         panic!("Expected Polygon, got {:?}", self);
     }
 
-    fn render_line(&self, cell_size: usize) -> svg::Node {
+    fn render_path(&self, cell_size: &CellSize) -> svg::Node {
+        if let Shape::Path(segments) = self {
+            let mut path = svg::Path::new();
+            let coords = |anchor: &Anchor| anchor.coords(cell_size);
+            for segment in segments {
+                match segment {
+                    PathSegment::MoveTo(end) => path.move_to_coords(coords(end)),
+                    PathSegment::LineTo(end) => path.line_to_coords(coords(end)),
+                    PathSegment::CurveTo(control1, control2, end) => path
+                        .curve_to_coords(
+                            coords(control1),
+                            coords(control2),
+                            coords(end),
+                        ),
+                    PathSegment::SmoothCurveTo(control2, end) => {
+                        path.smooth_curve_to_coords(coords(control2), coords(end))
+                    }
+                    PathSegment::QuadraticCurveTo(control, end) => path
+                        .quadratic_curve_to_coords(coords(control), coords(end)),
+                    PathSegment::SmoothQuadraticCurveTo(end) => {
+                        path.smooth_quadratic_curve_to_coords(coords(end))
+                    }
+                    PathSegment::Close => path.close(),
+                };
+            }
+            return path.node();
+        }
+
+        panic!("Expected Path, got {:?}", self);
+    }
+
+    fn render_line(&self, cell_size: &CellSize) -> svg::Node {
         if let Shape::Line(start, end, width) = self {
             return svg::tag("line")
                 .position_pair(*start, *end, cell_size)
@@ -207,73 +559,17 @@ impl Shape {
         panic!("Expected Line, got {:?}", self);
     }
 
-    fn render_curve(&self, cell_size: usize) -> svg::Node {
+    fn render_curve(&self, cell_size: &CellSize) -> svg::Node {
         if let Shape::CurveOutward(start, end, stroke_width)
         | Shape::CurveInward(start, end, stroke_width) = self
         {
             let inward = matches!(self, Shape::CurveInward(..));
 
-            let (start_x, start_y) = start.coords(cell_size);
-            let (end_x, end_y) = end.coords(cell_size);
-
-            let midpoint = ((start_x + end_x) / 2.0, (start_y + end_y) / 2.0);
-            let start_from_midpoint =
-                (start_x - midpoint.0, start_y - midpoint.1);
-            let end_from_midpoint = (end_x - midpoint.0, end_y - midpoint.1);
-
-            let control = {
-                let relative = (end_x - start_x, end_y - start_y);
-                if start_from_midpoint.0 * start_from_midpoint.1 > 0.0
-                    && end_from_midpoint.0 * end_from_midpoint.1 > 0.0
-                {
-                    if inward {
-                        (
-                            midpoint.0 + relative.0.abs() / 2.0,
-                            midpoint.1 - relative.1.abs() / 2.0,
-                        )
-                    } else {
-                        (
-                            midpoint.0 - relative.0.abs() / 2.0,
-                            midpoint.1 + relative.1.abs() / 2.0,
-                        )
-                    }
-                // diagonal line is going like this: /
-                } else if start_from_midpoint.0 * start_from_midpoint.1 < 0.0
-                    && end_from_midpoint.0 * end_from_midpoint.1 < 0.0
-                {
-                    if inward {
-                        (
-                            midpoint.0 - relative.0.abs() / 2.0,
-                            midpoint.1 - relative.1.abs() / 2.0,
-                        )
-                    } else {
-                        (
-                            midpoint.0 + relative.0.abs() / 2.0,
-                            midpoint.1 + relative.1.abs() / 2.0,
-                        )
-                    }
-                // line is horizontal
-                } else if start_y == end_y {
-                    (
-                        midpoint.0,
-                        midpoint.1
-                            + (if inward { -1.0 } else { 1.0 })
-                                * relative.0.abs()
-                                / 2.0,
-                    )
-                // line is vertical
-                } else if start_x == end_x {
-                    (
-                        midpoint.0
-                            + (if inward { -1.0 } else { 1.0 })
-                                * relative.1.abs()
-                                / 2.0,
-                        midpoint.1,
-                    )
-                } else {
-                    unreachable!()
-                }
-            };
+            let control = curve_control_point(
+                start.coords(cell_size),
+                end.coords(cell_size),
+                inward,
+            );
 
             let mut path = svg::Path::new();
             path.move_to(*start, cell_size);
@@ -289,7 +585,7 @@ impl Shape {
 
     fn render_circle(
         &self,
-        cell_size: usize,
+        cell_size: &CellSize,
         object_sizes: ObjectSizes,
     ) -> svg::Node {
         let center = match self {
@@ -307,15 +603,269 @@ impl Shape {
         let radius = match self {
             Shape::BigDot(_) => object_sizes.small_circle_radius,
             Shape::Dot(_) => object_sizes.dot_radius,
-            Shape::BigCircle(_) => cell_size as f32 / 2.0,
+            Shape::BigCircle(at) => {
+                cell_size.radius(0.5, at.as_centered().grid_coords())
+            }
             Shape::SmallCircle(_) => object_sizes.small_circle_radius,
             _ => unreachable!(),
         };
 
-        return svg::tag("circle")
+        svg::tag("circle")
             .attr("cx", center.0)
             .attr("cy", center.1)
             .attr("r", radius)
-            .into();
+            .into()
+    }
+
+    fn render_ellipse(&self, cell_size: &CellSize) -> svg::Node {
+        if let Shape::Ellipse(region) = self {
+            let (width, height) = region.size(cell_size);
+            let (x, y) = region.start.coords(cell_size);
+            let radii = (width / 2.0, height / 2.0);
+
+            // two half ellipses, since a single arc can't start and end at the same point
+            let mut path = svg::Path::new();
+            path.move_to_coords((x, y + radii.1));
+            path.arc_to_coords(radii, 0.0, false, true, (x + width, y + radii.1));
+            path.arc_to_coords(radii, 0.0, false, true, (x, y + radii.1));
+            path.close();
+            return path.node();
+        }
+
+        panic!("Expected Ellipse, got {:?}", self);
+    }
+
+    fn render_arc(
+        &self,
+        cell_size: &CellSize,
+        object_sizes: ObjectSizes,
+    ) -> svg::Node {
+        if let Shape::Arc {
+            center,
+            radius_cells,
+            start,
+            end,
+        } = self
+        {
+            let radius = cell_size.radius(*radius_cells, center.grid_coords());
+            let center = center.coords(cell_size);
+
+            let mut path = svg::Path::new();
+            path.move_to_coords(point_on_circle(center, radius, *start));
+            arc_around(&mut path, center, radius, *start, *end);
+            return path
+                .element()
+                .attr("stroke-width", object_sizes.default_line_width)
+                .into();
+        }
+
+        panic!("Expected Arc, got {:?}", self);
+    }
+
+    fn render_sector(&self, cell_size: &CellSize) -> svg::Node {
+        if let Shape::Sector {
+            center,
+            inner_radius_cells,
+            outer_radius_cells,
+            start,
+            end,
+        } = self
+        {
+            let inner =
+                cell_size.radius(*inner_radius_cells, center.grid_coords());
+            let outer =
+                cell_size.radius(*outer_radius_cells, center.grid_coords());
+            let center = center.coords(cell_size);
+            let full_turn = (*end - *start).degrees().abs() >= Angle::TURN.0;
+
+            let mut path = svg::Path::new();
+            path.move_to_coords(point_on_circle(center, outer, *start));
+            arc_around(&mut path, center, outer, *start, *end);
+
+            if inner > 0.0 {
+                // a full donut is two separate circles: the inner one, drawn the other way around, makes the hole
+                if full_turn {
+                    path.close();
+                    path.move_to_coords(point_on_circle(center, inner, *end));
+                } else {
+                    path.line_to_coords(point_on_circle(center, inner, *end));
+                }
+                arc_around(&mut path, center, inner, *end, *start);
+            } else if !full_turn {
+                path.line_to_coords(center);
+            }
+
+            path.close();
+            return path.node();
+        }
+
+        panic!("Expected Sector, got {:?}", self);
+    }
+}
+
+/// Adds arcs going around `center` from `start` to `end` to the path, whose current point must be at `start`.
+/// Arcs of a full turn or more are split in two, since a single arc can't start and end at the same point.
+fn arc_around(
+    path: &mut svg::Path,
+    center: (f32, f32),
+    radius: f32,
+    start: Angle,
+    end: Angle,
+) {
+    let sweep = (end - start).degrees().clamp(-Angle::TURN.0, Angle::TURN.0);
+    let clockwise = sweep > 0.0;
+    let radii = (radius, radius);
+
+    if sweep.abs() == Angle::TURN.0 {
+        let halfway = Angle::from_degrees(start.degrees() + sweep / 2.0);
+        path.arc_to_coords(
+            radii,
+            0.0,
+            false,
+            clockwise,
+            point_on_circle(center, radius, halfway),
+        );
+    }
+
+    path.arc_to_coords(
+        radii,
+        0.0,
+        sweep.abs() > Angle::TURN.0 / 2.0 && sweep.abs() < Angle::TURN.0,
+        clockwise,
+        point_on_circle(
+            center,
+            radius,
+            Angle::from_degrees(start.degrees() + sweep),
+        ),
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        ColorMapping, Object, Point, Region, graphics::shapes::TilingOptions,
+    };
+
+    fn render_tiling(options: TilingOptions) -> String {
+        let tile: Object =
+            Shape::Rectangle(Point::Corner(3, 3), Point::Corner(4, 4)).into();
+        Shape::Tiling(Region::from(((0, 0), (6, 6))), Box::new(tile), options)
+            .render_to_svg(
+                ColorMapping::default(),
+                &CellSize::square(10),
+                ObjectSizes::default(),
+                "tiling",
+            )
+            .unwrap()
+            .to_string()
+    }
+
+    #[test]
+    fn tilings_repeat_their_tile_through_a_pattern() {
+        let rendered = render_tiling(TilingOptions::default());
+        assert!(rendered.contains(r#"id="tiling-tiling""#));
+        assert!(rendered.contains(r#"fill="url(#tiling-tiling)""#));
+        // the pattern spans 2×2 tiles of the tile's size
+        assert!(rendered.contains(r#"width="20""#));
+        assert_eq!(rendered.matches("<use").count(), 3);
+    }
+
+    #[test]
+    fn staggered_tilings_wrap_around_the_pattern() {
+        let rendered =
+            render_tiling(TilingOptions::default().step(2.0, 1.0).stagger(1.0));
+        assert!(rendered.contains(r#"width="40""#));
+        assert!(rendered.contains(r#"height="20""#));
+        // the staggered tile overflows on the right, and is drawn again on the left
+        assert_eq!(rendered.matches("<use").count(), 4);
+    }
+
+    fn render(shape: Shape) -> String {
+        shape
+            .render_to_svg(
+                ColorMapping::default(),
+                &CellSize::square(10),
+                ObjectSizes::default(),
+                "shape",
+            )
+            .unwrap()
+            .to_string()
+    }
+
+    #[test]
+    fn full_turns_are_split_in_two_arcs() {
+        let arc = |end: f32| Shape::Arc {
+            center: Point::Corner(2, 2),
+            radius_cells: 1.0,
+            start: Angle::from_degrees(0.0),
+            end: Angle::from_degrees(end),
+        };
+        assert_eq!(render(arc(90.0)).matches("A ").count(), 1);
+        assert_eq!(render(arc(360.0)).matches("A ").count(), 2);
+        // large arc flag
+        assert!(render(arc(270.0)).contains("A 10 10 0 1 1"));
+    }
+
+    #[test]
+    fn donuts_have_a_hole() {
+        let sector = |inner: f32, end: f32| Shape::Sector {
+            center: Point::Corner(2, 2),
+            inner_radius_cells: inner,
+            outer_radius_cells: 2.0,
+            start: Angle::from_degrees(0.0),
+            end: Angle::from_degrees(end),
+        };
+
+        // pie slices go back to the center
+        assert!(render(sector(0.0, 90.0)).contains("L 20 20 Z"));
+        // full donuts are two circles
+        let donut = render(sector(1.0, 360.0));
+        assert_eq!(donut.matches("M ").count(), 2);
+        assert_eq!(donut.matches("A ").count(), 4);
+    }
+
+    #[test]
+    fn ellipses_are_two_half_arcs() {
+        let ellipse = render(Shape::Ellipse(Region::from(((0, 0), (3, 1)))));
+        assert!(ellipse.contains("A 20 10 0 0 1 40 10"));
+        assert!(ellipse.contains("A 20 10 0 0 1 0 10"));
+    }
+
+    fn raw_square() -> Shape {
+        Shape::raw_svg(
+            r##"<rect width="2" height="2" fill="#c0ffee"/>"##,
+            "#c0ffee",
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn raw_svg_renders_without_an_object() {
+        let rendered = render(raw_square());
+        assert!(rendered.contains("translate(0 0) scale(10 10)"));
+        assert!(rendered.contains(r#"data-object="shape""#));
+        assert!(rendered.contains("#c0ffee"));
+    }
+
+    #[test]
+    fn raw_svg_in_booleans_is_painted() {
+        let boolean = raw_square().difference(Shape::Rectangle(
+            Point::Corner(0, 0),
+            Point::Corner(1, 1),
+        ));
+        let rendered = render(boolean);
+        assert!(rendered.contains(r##"fill="white""##));
+        assert!(!rendered.contains("#c0ffee"));
+    }
+
+    #[test]
+    fn raw_svg_tiles() {
+        let tiling = Shape::Tiling(
+            Region::from(((0, 0), (4, 4))),
+            Box::new(raw_square().into()),
+            TilingOptions::default(),
+        );
+        assert!(render(tiling).contains("<pattern"));
     }
 }
@@ -0,0 +1,122 @@
+use super::CSSRenderable;
+use crate::{
+    ColorMapping, Fill,
+    graphics::stroke::{LineCap, LineJoin, Stroke},
+};
+use itertools::Itertools;
+
+impl CSSRenderable for Stroke {
+    fn render_to_css_filled(&self, colormap: &ColorMapping) -> String {
+        let mut css = match &self.fill {
+            Fill::Solid(color) => format!("stroke: {};", color.render(colormap)),
+            Fill::Translucent(color, opacity) => format!(
+                "stroke: {}; stroke-opacity: {};",
+                color.render(colormap),
+                opacity
+            ),
+            Fill::Dotted(..)
+            | Fill::Hatches(..)
+            | Fill::LinearGradient(..)
+            | Fill::RadialGradient(..) => {
+                format!("stroke: url(#{});", self.fill.pattern_id())
+            }
+        };
+
+        css += &format!(
+            " stroke-width: {}; stroke-linecap: {}; stroke-linejoin: {}; stroke-miterlimit: {};",
+            self.width,
+            match self.cap {
+                LineCap::Butt => "butt",
+                LineCap::Round => "round",
+                LineCap::Square => "square",
+            },
+            match self.join {
+                LineJoin::Miter => "miter",
+                LineJoin::Round => "round",
+                LineJoin::Bevel => "bevel",
+            },
+            self.miter_limit
+        );
+
+        if !self.dash_array.is_empty() {
+            css += &format!(
+                " stroke-dasharray: {}; stroke-dashoffset: {};",
+                self.dash_array.iter().join(" "),
+                self.dash_offset
+            );
+        }
+
+        css
+    }
+
+    fn render_to_css_stroked(&self, colormap: &ColorMapping) -> String {
+        self.render_to_css_filled(colormap)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        Angle, CellSize, Color, Object, ObjectSizes, Point, Shape,
+        rendering::renderable::SVGRenderable,
+    };
+
+    #[test]
+    fn dashed_strokes() {
+        let css = Color::Red
+            .stroke(2.0)
+            .dashed(vec![4.0, 2.0])
+            .dash_offset(1.0)
+            .capped(LineCap::Round)
+            .joined(LineJoin::Bevel)
+            .render_to_css_filled(&ColorMapping::default());
+
+        assert!(css.starts_with("stroke: red;"));
+        assert!(css.contains("stroke-width: 2;"));
+        assert!(css.contains("stroke-linecap: round;"));
+        assert!(css.contains("stroke-linejoin: bevel;"));
+        assert!(css.contains("stroke-dasharray: 4 2; stroke-dashoffset: 1;"));
+    }
+
+    #[test]
+    fn solid_strokes_have_no_dashes() {
+        let css = Color::Red
+            .stroke(1.0)
+            .render_to_css_filled(&ColorMapping::default());
+        assert!(!css.contains("stroke-dasharray"));
+    }
+
+    #[test]
+    fn patterned_strokes() {
+        let hatches =
+            Fill::Hatches(Color::Blue, Angle::from_degrees(45.0), 0.5, 2.0);
+        let css = Stroke::new(hatches.clone(), 1.0)
+            .render_to_css_filled(&ColorMapping::default());
+        assert!(
+            css.starts_with(&format!("stroke: url(#{});", hatches.pattern_id()))
+        );
+    }
+
+    #[test]
+    fn filled_shapes_can_be_outlined() {
+        let rendered = Object::from(Shape::Rectangle(
+            Point::Corner(0, 0),
+            Point::Corner(1, 1),
+        ))
+        .colored(Color::Red)
+        .outlined(Color::Blue, 3.0)
+        .render_to_svg(
+            ColorMapping::default(),
+            &CellSize::square(10),
+            ObjectSizes::default(),
+            "outlined",
+        )
+        .unwrap()
+        .to_string();
+
+        assert!(rendered.contains("fill: red;"));
+        assert!(rendered.contains("stroke: blue;"));
+        assert!(rendered.contains("stroke-width: 3;"));
+    }
+}
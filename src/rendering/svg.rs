@@ -3,12 +3,12 @@ use std::{collections::HashMap, fmt::Display};
 use itertools::Itertools;
 use measure_time::debug_time;
 
-use crate::{Color, ColorMapping, Point, Region};
+use crate::{CellSize, Color, ColorMapping, Point, Region, Topology};
 
 pub fn stringify_svg(element: Node) -> String {
     debug_time!("stringify_svg");
 
-    return element.to_string();
+    element.to_string()
 }
 
 #[derive(Debug, Clone)]
@@ -26,9 +26,9 @@ pub enum Node {
     SVG(String),
 }
 
-impl Into<Node> for Element {
-    fn into(self) -> Node {
-        self.node()
+impl From<Element> for Node {
+    fn from(element: Element) -> Self {
+        element.node()
     }
 }
 
@@ -36,6 +36,27 @@ pub fn tag(tag_name: &str) -> Element {
     Element::new(tag_name)
 }
 
+/// Element covering exactly the cells of the region: a rectangle on square grids, and a path following the cells' outline otherwise
+pub fn cells(region: impl Into<Region>, cell_size: &CellSize) -> Element {
+    let region = region.into();
+    if cell_size.topology == Topology::Square {
+        return tag("rect").region(region, cell_size);
+    }
+
+    let mut path = Path::new();
+    for outline in cell_size.outlines(&region) {
+        for (i, point) in outline.into_iter().enumerate() {
+            if i == 0 {
+                path.move_to_coords(point);
+            } else {
+                path.line_to_coords(point);
+            }
+        }
+        path.close();
+    }
+    path.element()
+}
+
 pub fn node(tag_name: &str) -> Node {
     tag(tag_name).node()
 }
@@ -80,12 +101,16 @@ impl Element {
         self.attr("x", x).attr("y", y)
     }
 
-    pub fn fill(self, c: Color, colormap: &ColorMapping) -> Self {
+    pub fn fill(self, c: &Color, colormap: &ColorMapping) -> Self {
         self.attr("fill", c.render(colormap))
     }
 
     /// Sets cx and cy
-    pub fn center_position(self, p: impl Into<Point>, cell_size: usize) -> Self {
+    pub fn center_position(
+        self,
+        p: impl Into<Point>,
+        cell_size: &CellSize,
+    ) -> Self {
         let (x, y) = p.into().coords(cell_size);
         self.attr("cx", x).attr("cy", y)
     }
@@ -95,7 +120,7 @@ impl Element {
         self,
         p1: impl Into<Point>,
         p2: impl Into<Point>,
-        cell_size: usize,
+        cell_size: &CellSize,
     ) -> Self {
         let (x1, y1) = p1.into().coords(cell_size);
         let (x2, y2) = p2.into().coords(cell_size);
@@ -106,7 +131,7 @@ impl Element {
     }
 
     /// Sets x and y
-    pub fn position(self, p: impl Into<Point>, cell_size: usize) -> Self {
+    pub fn position(self, p: impl Into<Point>, cell_size: &CellSize) -> Self {
         self.coords(p.into().coords(cell_size))
     }
 
@@ -120,15 +145,16 @@ impl Element {
     }
 
     /// Sets width and height
-    pub fn size(self, r: impl Into<Region>, cell_size: usize) -> Self {
+    pub fn size(self, r: impl Into<Region>, cell_size: &CellSize) -> Self {
         self.dimensions(r.into().size(cell_size))
     }
 
-    /// Sets x, y, width and height according to the region
-    pub fn region(self, r: impl Into<Region>, cell_size: usize) -> Self {
-        let region: Region = r.into();
-        self.position(region.start, cell_size)
-            .size(region, cell_size)
+    /// Sets x, y, width and height according to the region.
+    /// On grids that are not square, this is the smallest rectangle containing the region, see `cells` for its exact shape.
+    pub fn region(self, r: impl Into<Region>, cell_size: &CellSize) -> Self {
+        let ((left, top), (right, bottom)) = cell_size.bounds(&r.into());
+        self.coords((left, top))
+            .dimensions((right - left, bottom - top))
     }
 
     pub fn style(self, key: &str, value: impl Display) -> Self {
@@ -198,6 +224,7 @@ pub enum PathInstruction {
     ClosePath,
 }
 
+#[derive(Default)]
 pub struct Path(Vec<PathInstruction>);
 
 impl Path {
@@ -216,7 +243,7 @@ impl Path {
     pub fn move_to(
         &mut self,
         p: impl Into<Point>,
-        cell_size: usize,
+        cell_size: &CellSize,
     ) -> &mut Self {
         self.0
             .push(PathInstruction::MoveTo(p.into().coords(cell_size)));
@@ -226,7 +253,7 @@ impl Path {
     pub fn line_to(
         &mut self,
         p: impl Into<Point>,
-        cell_size: usize,
+        cell_size: &CellSize,
     ) -> &mut Self {
         self.0
             .push(PathInstruction::LineTo(p.into().coords(cell_size)));
@@ -237,7 +264,7 @@ impl Path {
         &mut self,
         control: impl Into<(f32, f32)>,
         end: impl Into<Point>,
-        cell_size: usize,
+        cell_size: &CellSize,
     ) -> &mut Self {
         self.0.push(PathInstruction::QuadraticCurveTo(
             control.into(),
@@ -246,6 +273,87 @@ impl Path {
         self
     }
 
+    /// Like `move_to`, but with coordinates already in pixels
+    pub fn move_to_coords(&mut self, p: impl Into<(f32, f32)>) -> &mut Self {
+        self.0.push(PathInstruction::MoveTo(p.into()));
+        self
+    }
+
+    /// Like `line_to`, but with coordinates already in pixels
+    pub fn line_to_coords(&mut self, p: impl Into<(f32, f32)>) -> &mut Self {
+        self.0.push(PathInstruction::LineTo(p.into()));
+        self
+    }
+
+    /// Cubic Bézier curve, with all coordinates in pixels
+    pub fn curve_to_coords(
+        &mut self,
+        control1: impl Into<(f32, f32)>,
+        control2: impl Into<(f32, f32)>,
+        end: impl Into<(f32, f32)>,
+    ) -> &mut Self {
+        self.0.push(PathInstruction::CurveTo(
+            control1.into(),
+            control2.into(),
+            end.into(),
+        ));
+        self
+    }
+
+    /// Cubic Bézier curve whose first control point reflects the previous curve's, with coordinates in pixels
+    pub fn smooth_curve_to_coords(
+        &mut self,
+        control2: impl Into<(f32, f32)>,
+        end: impl Into<(f32, f32)>,
+    ) -> &mut Self {
+        self.0
+            .push(PathInstruction::SmoothCurveTo(control2.into(), end.into()));
+        self
+    }
+
+    /// Like `quadratic_curve_to`, but with coordinates already in pixels
+    pub fn quadratic_curve_to_coords(
+        &mut self,
+        control: impl Into<(f32, f32)>,
+        end: impl Into<(f32, f32)>,
+    ) -> &mut Self {
+        self.0.push(PathInstruction::QuadraticCurveTo(
+            control.into(),
+            end.into(),
+        ));
+        self
+    }
+
+    /// Quadratic Bézier curve whose control point reflects the previous curve's, with coordinates in pixels
+    pub fn smooth_quadratic_curve_to_coords(
+        &mut self,
+        end: impl Into<(f32, f32)>,
+    ) -> &mut Self {
+        self.0
+            .push(PathInstruction::SmoothQuadraticCurveTo(end.into()));
+        self
+    }
+
+    /// Elliptical arc from the current point to `end`, both radii and `end` in pixels.
+    /// `rotation` is in degrees, `sweep` draws the arc clockwise.
+    pub fn arc_to_coords(
+        &mut self,
+        radii: (f32, f32),
+        rotation: f32,
+        large_arc: bool,
+        sweep: bool,
+        end: impl Into<(f32, f32)>,
+    ) -> &mut Self {
+        self.0.push(PathInstruction::ArcTo(
+            radii,
+            rotation,
+            large_arc,
+            sweep,
+            end.into(),
+        ));
+        self
+    }
+
     pub fn close(&mut self) -> &mut Self {
         self.0.push(PathInstruction::ClosePath);
         self
@@ -293,7 +401,8 @@ impl Display for PathInstruction {
             ) => {
                 write!(
                     f,
-                    "A {rx} {ry} {angle} {large_arc_flag} {sweep_flag} {x2} {y2}"
+                    "A {rx} {ry} {angle} {} {} {x2} {y2}",
+                    *large_arc_flag as u8, *sweep_flag as u8
                 )
             }
             Self::ClosePath => write!(f, "Z"),
@@ -343,7 +452,7 @@ impl Display for Node {
                     write!(
                         f,
                         r#"{spacing}style="{value}""#,
-                        spacing = space_if(non_style_attributes.len() > 0),
+                        spacing = space_if(!non_style_attributes.is_empty()),
                         value = styles
                             .iter()
                             .map(|(k, v)| format!("{k}: {v};"))
@@ -356,9 +465,9 @@ impl Display for Node {
                 }
 
                 if children.is_empty() {
-                    write!(f, "/>\n")?;
+                    writeln!(f, "/>")?;
                 } else {
-                    write!(f, ">\n")?;
+                    writeln!(f, ">")?;
 
                     for child in children {
                         write!(f, "{}", child)?;
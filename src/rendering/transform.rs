@@ -1,7 +1,7 @@
 use measure_time::debug_time;
 
 use super::SVGAttributesRenderable;
-use crate::{ColorMapping, ObjectSizes, Transformation};
+use crate::{CellSize, ColorMapping, ObjectSizes, Transformation};
 use std::collections::HashMap;
 
 impl SVGAttributesRenderable for Transformation {
@@ -10,7 +10,7 @@ impl SVGAttributesRenderable for Transformation {
     fn render_to_svg_attributes(
         &self,
         _colormap: ColorMapping,
-        _cell_size: usize,
+        _cell_size: &CellSize,
         _object_sizes: ObjectSizes,
         _id: &str,
     ) -> anyhow::Result<HashMap<String, String>> {
@@ -73,7 +73,9 @@ impl Syncable for MidiSynchronizer {
                         amplitudes.push(last_amplitude);
                     }
 
-                    progressbar.map(|bar| bar.inc(1));
+                    if let Some(bar) = progressbar {
+                        bar.inc(1);
+                    }
 
                     (
                         name.clone(),
@@ -113,6 +115,12 @@ struct Now {
 type Timeline<'a> = HashMap<u32, HashMap<String, TrackEvent<'a>>>;
 
 type StemNotes = HashMap<u32, HashMap<String, Note>>;
+/// Tempo, notes of each track and markers of a MIDI file
+type MidiContents = (
+    Now,
+    HashMap<String, Vec<Note>>,
+    HashMap<TimestampMS, String>,
+);
 
 impl Note {
     fn is_off(&self) -> bool {
@@ -148,11 +156,7 @@ impl Debug for Note {
 fn load_midi_file(
     source: &PathBuf,
     progressbar: Option<&ProgressBar>,
-) -> Result<(
-    Now,
-    HashMap<String, Vec<Note>>,
-    HashMap<TimestampMS, String>,
-)> {
+) -> Result<MidiContents> {
     debug_time!("load_midi_notes");
 
     let mut markers = HashMap::<TimestampMS, String>::new();
@@ -194,10 +198,10 @@ fn load_midi_file(
                 TrackEventKind::Meta(MetaMessage::TrackName(name_bytes)) => {
                     track_name = String::from_utf8(name_bytes.to_vec())?;
                 }
-                TrackEventKind::Meta(MetaMessage::Tempo(tempo)) => {
-                    if now.tempo == 0 {
-                        now.tempo = tempo.as_int() as usize;
-                    }
+                TrackEventKind::Meta(MetaMessage::Tempo(tempo))
+                    if now.tempo == 0 =>
+                {
+                    now.tempo = tempo.as_int() as usize;
                 }
                 _ => {}
             }
@@ -1,6 +1,8 @@
 pub mod log;
 pub use log::*;
+#[cfg(feature = "video")]
 pub mod pretty;
+#[cfg(feature = "video")]
 pub(crate) use pretty::*;
 pub mod progress;
 pub mod spinner;
@@ -1,8 +1,10 @@
-use crate::{Canvas, context::Context, video::hooks::InnerHook};
+use crate::{Canvas, Shape, context::Context, video::hooks::InnerHook};
+use anyhow::anyhow;
 use easing_function::Easing;
 pub use easing_function::{EasingFunction, easings};
 use nanoid::nanoid;
 use std::fmt::Display;
+use std::sync::Mutex;
 
 /// Arguments: animation progress (from 0.0 to 1.0), canvas, current ms
 pub type AnimationUpdateFunction =
@@ -99,4 +101,40 @@ impl<C: Default> Context<'_, C> {
             Animation::new(format!("unnamed animation {}", nanoid!()), f),
         );
     }
+
+    /// Morphs the object named `object` in `layer` into `target`, see `Shape::interpolate`.
+    /// The object's shape is captured when the animation starts. duration is in milliseconds
+    pub fn animate_shape(
+        &mut self,
+        layer: &str,
+        object: &str,
+        target: Shape,
+        duration: usize,
+        easing: impl Into<EasingFunction>,
+    ) {
+        let (layer, object) = (layer.to_string(), object.to_string());
+        let initial: Mutex<Option<Shape>> = Mutex::new(None);
+
+        self.start_animation(
+            duration,
+            easing,
+            Animation::from((
+                format!("morph {layer}/{object}"),
+                Box::new(move |t, canvas: &mut Canvas, _| {
+                    let current = canvas
+                        .layer(&layer)?
+                        .safe_object(&object)
+                        .ok_or_else(|| {
+                            anyhow!("No object named {object} in layer {layer}")
+                        })?;
+
+                    let mut initial = initial.lock().unwrap();
+                    let from =
+                        initial.get_or_insert_with(|| current.shape.clone());
+                    current.shape = from.interpolate(&target, t);
+                    Ok(())
+                }) as Box<AnimationUpdateFunction>,
+            )),
+        );
+    }
 }
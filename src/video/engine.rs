@@ -161,7 +161,7 @@ impl<C: Default> Video<C> {
                         size: (canvas.width(), canvas.height()),
                         svg: canvas.render_to_svg(
                             canvas.colormap.clone(),
-                            canvas.cell_size,
+                            &canvas.cell_size,
                             canvas.object_sizes,
                             "",
                         )?,
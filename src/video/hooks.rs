@@ -4,7 +4,11 @@ use crate::synchronization::audio::MusicalDurationUnit;
 use crate::{Canvas, Object};
 use anyhow::Result;
 use chrono::NaiveDateTime;
-use std::{fmt::Formatter, panic};
+use std::{
+    fmt::Formatter,
+    panic,
+    sync::atomic::{AtomicBool, Ordering},
+};
 
 pub type BeatNumber = usize;
 pub type FrameNumber = usize;
@@ -270,6 +274,33 @@ pub trait AttachHooks<C>: Sized {
         })
     }
 
+    /// Triggers when the two objects start touching, see `Shape::intersects`.
+    /// Objects are addressed by path, see `Canvas::object`.
+    fn on_collision(
+        self,
+        first: &'static str,
+        second: &'static str,
+        render_function: &'static RenderFunction<C>,
+    ) -> Self {
+        let touching = AtomicBool::new(false);
+        self.with_hook(Hook {
+            when: Box::new(move |canvas, _, _, _| {
+                let collides =
+                    match (canvas.find_object(first), canvas.find_object(second))
+                    {
+                        (Some(first), Some(second)) => {
+                            first.shape.intersects(&second.shape)
+                        }
+                        _ => false,
+                    };
+
+                let was_touching = touching.swap(collides, Ordering::Relaxed);
+                collides && !was_touching
+            }),
+            render_function: Box::new(render_function),
+        })
+    }
+
     fn at_frame(
         self,
         frame: usize,
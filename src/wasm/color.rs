@@ -0,0 +1,52 @@
+use crate::{Color, ColorMapping};
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::{JsValue, convert::FromWasmAbi, convert::IntoWasmAbi};
+
+// Colors are passed around as their name, since enums with data can't be exported
+
+impl From<Color> for JsValue {
+    fn from(val: Color) -> Self {
+        JsValue::from_str(&val.name())
+    }
+}
+
+impl wasm_bindgen::describe::WasmDescribe for Color {
+    fn describe() {
+        JsValue::describe()
+    }
+}
+
+impl wasm_bindgen::convert::IntoWasmAbi for Color {
+    type Abi = <JsValue as IntoWasmAbi>::Abi;
+
+    fn into_abi(self) -> Self::Abi {
+        JsValue::from(self).into_abi()
+    }
+}
+
+impl wasm_bindgen::convert::FromWasmAbi for Color {
+    type Abi = <JsValue as FromWasmAbi>::Abi;
+
+    unsafe fn from_abi(js: Self::Abi) -> Self {
+        let name = unsafe { JsValue::from_abi(js) }
+            .as_string()
+            .expect("Colors must be given by name");
+        Color::named(&name)
+    }
+}
+
+// Loaders can't be bound directly, since their errors are not JsValues
+#[wasm_bindgen]
+impl ColorMapping {
+    #[wasm_bindgen(js_name = from_css)]
+    pub fn from_css_web(content: &str) -> Result<ColorMapping, JsValue> {
+        ColorMapping::from_css(content)
+            .map_err(|e| JsValue::from_str(&e.to_string()))
+    }
+
+    #[wasm_bindgen(js_name = from_json)]
+    pub fn from_json_web(content: &str) -> Result<ColorMapping, JsValue> {
+        ColorMapping::from_json(content)
+            .map_err(|e| JsValue::from_str(&e.to_string()))
+    }
+}
@@ -1,6 +1,6 @@
 use super::canvas;
 use crate::{
-    Color, Fill, Filter, Layer, Object, Point, Shape,
+    Color, Fill, Filter, Layer, Point, Shape,
     wasm::{RNG, append_new_div_inside, render_canvas, replace_content_with},
 };
 use wasm_bindgen::prelude::wasm_bindgen;
@@ -1,9 +1,9 @@
+pub mod color;
 pub mod layer;
 pub mod point;
 pub mod transform;
 pub mod web;
 
 pub use layer::*;
-pub use point::*;
 pub use transform::*;
 pub use web::*;
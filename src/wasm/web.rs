@@ -43,20 +43,20 @@ macro_rules! console_log {
 
 #[wasm_bindgen]
 pub fn render_image(opacity: f32, color: Color) -> Result<(), JsValue> {
-    let mut canvas = Canvas::with_colors(ColorMapping {
-        black: "#ffffff".into(),
-        white: "#ffffff".into(),
-        red: "#cf0a2b".into(),
-        green: "#22e753".into(),
-        blue: "#2734e6".into(),
-        yellow: "#f8e21e".into(),
-        orange: "#f05811".into(),
-        purple: "#6a24ec".into(),
-        brown: "#a05634".into(),
-        pink: "#e92e76".into(),
-        gray: "#81a0a8".into(),
-        cyan: "#4fecec".into(),
-    });
+    let mut canvas = Canvas::with_colors(ColorMapping::from([
+        ("black", "#ffffff"),
+        ("white", "#ffffff"),
+        ("red", "#cf0a2b"),
+        ("green", "#22e753"),
+        ("blue", "#2734e6"),
+        ("yellow", "#f8e21e"),
+        ("orange", "#f05811"),
+        ("purple", "#6a24ec"),
+        ("brown", "#a05634"),
+        ("pink", "#e92e76"),
+        ("gray", "#81a0a8"),
+        ("cyan", "#4fecec"),
+    ]));
 
     *WEB_CANVAS.lock().unwrap() = canvas;
     render_canvas_at(String::from("body"));
@@ -141,7 +141,7 @@ pub fn render_canvas() -> String {
     let can = canvas();
     can.render_to_svg(
         can.colormap.clone(),
-        can.cell_size,
+        &can.cell_size,
         can.object_sizes,
         "web_root_canvas",
     )